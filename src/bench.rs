@@ -0,0 +1,216 @@
+use std::{
+    fmt::Write,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::ParseError,
+    solver::{Day, Part},
+};
+
+/// Timing statistics of repeated runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    pub runs: usize,
+    #[serde(serialize_with = "as_nanos")]
+    pub min: Duration,
+    #[serde(serialize_with = "as_nanos")]
+    pub median: Duration,
+    #[serde(serialize_with = "as_nanos")]
+    pub mean: Duration,
+    #[serde(serialize_with = "as_nanos")]
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Returns `None` for no samples.
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        let mut samples = samples.to_vec();
+        samples.sort();
+
+        let runs = samples.len();
+        let min = *samples.first()?;
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+
+        let nanos: Vec<f64> = samples.iter().map(|s| s.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / runs as f64;
+        // Sample standard deviation, a single run has none
+        let variance = if runs > 1 {
+            nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            runs,
+            min,
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+}
+
+fn as_nanos<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+/// What was measured, parsing is timed separately from solving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::PartOne => "part one",
+            Stage::PartTwo => "part two",
+        }
+    }
+}
+
+impl From<Part> for Stage {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Stage::PartOne,
+            Part::Two => Stage::PartTwo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Row {
+    pub day: u8,
+    pub stage: Stage,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+/// Benchmark results of one or more days.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Report(pub Vec<Row>);
+
+impl Report {
+    pub fn to_markdown(&self) -> String {
+        let mut table = String::from(
+            "| Day | Stage | Runs | Min | Median | Mean | Std dev |\n\
+             |----:|-------|-----:|----:|-------:|-----:|--------:|\n",
+        );
+
+        for row in &self.0 {
+            let stats = &row.stats;
+            writeln!(
+                table,
+                "| {} | {} | {} | {:.2?} | {:.2?} | {:.2?} | {:.2?} |",
+                row.day,
+                row.stage.name(),
+                stats.runs,
+                stats.min,
+                stats.median,
+                stats.mean,
+                stats.stddev
+            )
+            .unwrap();
+        }
+
+        table
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is always serializable")
+    }
+}
+
+/// Parses the input and solves every implemented part `runs` times.
+pub fn bench_day(day: &Day, input: &str, runs: usize) -> Result<Vec<Row>, ParseError> {
+    let mut samples = Vec::with_capacity(runs);
+    let parsed = day.parse(input)?;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = black_box(day.parse(black_box(input)));
+        samples.push(start.elapsed());
+        // Dropped after timing, deallocating is not part of parsing
+        drop(result?);
+    }
+
+    let mut rows = vec![];
+    rows.extend(Stats::new(&samples).map(|stats| Row {
+        day: day.number,
+        stage: Stage::Parse,
+        stats,
+    }));
+
+    for part in Part::ALL {
+        // Skip parts that are not implemented
+        let Some(result) = day.solve(&parsed, part) else {
+            continue;
+        };
+        result?;
+
+        samples.clear();
+        for _ in 0..runs {
+            let start = Instant::now();
+            let answer = black_box(day.solve(black_box(&parsed), part));
+            samples.push(start.elapsed());
+            drop(answer);
+        }
+
+        rows.extend(Stats::new(&samples).map(|stats| Row {
+            day: day.number,
+            stage: part.into(),
+            stats,
+        }));
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&millis(&[4, 2, 8, 6])).unwrap();
+
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        // sqrt(20 / 3) ms
+        assert_eq!(stats.stddev.as_micros(), 2581);
+
+        assert!(Stats::new(&[]).is_none());
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let day = registry::find(6).unwrap();
+        let report = Report(bench_day(day, "Time: 7 15\nDistance: 9 40", 3).unwrap());
+
+        let stages: Vec<Stage> = report.0.iter().map(|r| r.stage).collect();
+        assert_eq!(stages, [Stage::Parse, Stage::PartOne, Stage::PartTwo]);
+        assert!(report.to_markdown().contains("| 6 | part two | 3 |"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json[0]["stage"], "parse");
+        assert_eq!(json[0]["runs"], 3);
+    }
+}
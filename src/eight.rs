@@ -1,12 +1,52 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
-fn part_one(input: &str) -> u32 {
-    let (instructions, nodes) = parse_input(input);
+use crate::{
+    error::{end_of, ParseError},
+    solver::{Answer, Solver},
+};
 
+const DAY: u8 = 8;
+
+pub struct Eight;
+
+impl Solver for Eight {
+    type Parsed = (Instructions, Nodes);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one((instructions, nodes): &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(instructions.clone(), nodes)?.into())
+    }
+
+    fn part_two((instructions, nodes): &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(instructions.clone(), nodes).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let (instructions, nodes) = parse_input(input)?;
+
+    solve_one(instructions, &nodes)
+}
+
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let (instructions, nodes) = parse_input(input)?;
+
+    Ok(solve_two(instructions, &nodes))
+}
+
+fn solve_one(instructions: Instructions, nodes: &Nodes) -> Result<u32, ParseError> {
     let start_key = NodeKey("AAA".to_string());
     let end_key = NodeKey("ZZZ".to_string());
 
-    let mut current_node = nodes.get(&start_key).unwrap();
+    let mut current_node = nodes
+        .get(&start_key)
+        .ok_or_else(|| missing_node(nodes, &start_key))?;
+    if !nodes.contains_key(&end_key) {
+        return Err(missing_node(nodes, &end_key));
+    }
 
     let mut steps = 0;
     for instruction in instructions.into_iter() {
@@ -14,18 +54,22 @@ fn part_one(input: &str) -> u32 {
         let next_key = current_node.get_direction_key(&instruction);
 
         if next_key == &end_key {
-            return steps;
+            return Ok(steps);
         }
 
         current_node = nodes.get(next_key).unwrap();
     }
 
-    steps
+    Ok(steps)
 }
 
-fn part_two(input: &str) -> u32 {
-    let (instructions, nodes) = parse_input(input);
+/// Error at the end of the input, after the last node line, for a node
+/// that part one needs.
+fn missing_node(nodes: &Nodes, key: &NodeKey) -> ParseError {
+    ParseError::new(DAY, 1, format!("a node `{}`", key.0), "").on_line(nodes.len() + 3)
+}
 
+fn solve_two(instructions: Instructions, nodes: &Nodes) -> u32 {
     let mut current_nodes: Vec<&Node> = nodes.values().filter(|v| v.key.0.ends_with('A')).collect();
 
     let mut steps = 0;
@@ -52,53 +96,59 @@ fn part_two(input: &str) -> u32 {
 
     steps
 }
-fn parse_input(input: &str) -> (Instructions, Nodes) {
+pub fn parse_input(input: &str) -> Result<(Instructions, Nodes), ParseError> {
     let mut lines = input.lines();
 
-    let instructions = Instructions::from(lines.next().unwrap());
+    let instructions: Instructions = lines.next().unwrap_or_default().parse()?;
 
-    // Skip empty line
-    lines.next();
+    let separator = lines.next().unwrap_or_default();
+    if !separator.is_empty() {
+        return Err(ParseError::at(DAY, separator, separator, "an empty line").on_line(2));
+    }
 
     let mut nodes = Nodes::new();
-    for line in lines {
-        // Remove unneeded characters
-        let line = line.replace([' ', '(', ')'], "");
-
-        let mut split = line.split('=');
-
-        let key = split.next().unwrap();
-        let key = NodeKey(key.to_string());
-
-        let node = split.next().unwrap();
-        let mut node = node.split(',');
-
+    let mut targets = vec![];
+    for (idx, line) in lines.enumerate() {
+        let (key, left, right) = split_node(line).map_err(|e| e.on_line(idx + 3))?;
         let node = Node {
-            key: key.clone(),
-            left: NodeKey(node.next().unwrap().to_string()),
-            right: NodeKey(node.next().unwrap().to_string()),
+            key: NodeKey(key.to_string()),
+            left: NodeKey(left.to_string()),
+            right: NodeKey(right.to_string()),
         };
 
-        nodes.insert(key, node);
+        if nodes.contains_key(&node.key) {
+            let error = ParseError::at(DAY, line, key, "a new node key");
+            return Err(error.on_line(idx + 3));
+        }
+        nodes.insert(node.key.clone(), node);
+        targets.extend([(idx + 3, line, left), (idx + 3, line, right)]);
+    }
+
+    // Every target must be a node of its own
+    for (line_number, line, target) in targets {
+        if !nodes.contains_key(&NodeKey(target.to_string())) {
+            let error = ParseError::at(DAY, line, target, "a defined node key");
+            return Err(error.on_line(line_number));
+        }
     }
 
-    (instructions, nodes)
+    Ok((instructions, nodes))
 }
 
-type Nodes = HashMap<NodeKey, Node>;
+pub type Nodes = HashMap<NodeKey, Node>;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct NodeKey(String);
+pub struct NodeKey(pub String);
 
 #[derive(Debug, Clone)]
-struct Node {
-    key: NodeKey,
-    left: NodeKey,
-    right: NodeKey,
+pub struct Node {
+    pub key: NodeKey,
+    pub left: NodeKey,
+    pub right: NodeKey,
 }
 
 impl Node {
-    fn get_direction_key(&self, instruction: &Instruction) -> &NodeKey {
+    pub fn get_direction_key(&self, instruction: &Instruction) -> &NodeKey {
         match instruction {
             Instruction::Left => &self.left,
             Instruction::Right => &self.right,
@@ -107,15 +157,15 @@ impl Node {
 }
 
 #[derive(Debug, Clone)]
-enum Instruction {
+pub enum Instruction {
     Left,
     Right,
 }
 
-#[derive(Debug)]
-struct Instructions {
+#[derive(Debug, Clone)]
+pub struct Instructions {
     step: usize,
-    instructions: Vec<Instruction>,
+    pub instructions: Vec<Instruction>,
 }
 
 impl Iterator for Instructions {
@@ -134,29 +184,78 @@ impl Iterator for Instructions {
     }
 }
 
-impl From<&str> for Instructions {
-    fn from(value: &str) -> Self {
+impl FromStr for Instructions {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            return Err(ParseError::new(DAY, 1, "`L` or `R` instructions", ""));
+        }
+
         let instructions = value
             .chars()
-            .map(|c| match c {
-                'R' => Instruction::Right,
-                'L' => Instruction::Left,
-                _ => unreachable!(),
+            .enumerate()
+            .map(|(idx, c)| match c {
+                'R' => Ok(Instruction::Right),
+                'L' => Ok(Instruction::Left),
+                _ => Err(ParseError::new(DAY, idx + 1, "`L` or `R`", &c.to_string())),
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        Self {
+        Ok(Self {
             step: 0,
             instructions,
-        }
+        })
     }
 }
 
+impl FromStr for Node {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key, left, right) = split_node(value)?;
+
+        Ok(Self {
+            key: NodeKey(key.to_string()),
+            left: NodeKey(left.to_string()),
+            right: NodeKey(right.to_string()),
+        })
+    }
+}
+
+/// Key, left and right target of a node line, as slices of it.
+fn split_node(value: &str) -> Result<(&str, &str, &str), ParseError> {
+    let (key, targets) = value
+        .split_once('=')
+        .ok_or_else(|| ParseError::at(DAY, value, end_of(value), "`=` after the node key"))?;
+
+    let targets = targets.trim();
+    let (left, right) = targets
+        .strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .and_then(|t| t.split_once(','))
+        .ok_or_else(|| ParseError::at(DAY, value, targets, "`(left, right)`"))?;
+
+    Ok((
+        node_key(value, key)?,
+        node_key(value, left)?,
+        node_key(value, right)?,
+    ))
+}
+
+fn node_key<'a>(line: &str, key: &'a str) -> Result<&'a str, ParseError> {
+    let key = key.trim();
+    if key.is_empty() || key.contains(' ') {
+        return Err(ParseError::at(DAY, line, key, "a node key"));
+    }
+
+    Ok(key)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/eight.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     const SAMPLE_INPUT: &str = "RL
 
@@ -187,28 +286,72 @@ XXX = (XXX, XXX)";
 
     #[test]
     fn test_one() {
-        let res = part_one(SAMPLE_INPUT);
+        let res = part_one(SAMPLE_INPUT).unwrap();
         assert_eq!(res, 2);
 
-        let res = part_one(SAMPLE_INPUT_2);
+        let res = part_one(SAMPLE_INPUT_2).unwrap();
         assert_eq!(res, 6);
     }
 
     #[test]
     fn solution_one() {
-        let res = part_one(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("eight") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("eight", Part::One));
     }
 
     #[test]
     fn test_two() {
-        let res = part_two(SAMPLE_INPUT_3);
+        let res = part_two(SAMPLE_INPUT_3).unwrap();
         assert_eq!(res, 6);
     }
 
     #[test]
     fn solution_two() {
-        let res = part_two(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("eight") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("eight", Part::Two));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = parse_input("RLX\n\nAAA = (BBB, CCC)").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+
+        let error = parse_input("RL\n\nAAA = (BBB, CCC)\nBBB = BBB, CCC").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 7));
+        assert_eq!(error.expected, "`(left, right)`");
+
+        let error = parse_input("RL\n\nAAA = (BBB, AAA)\nBBB = (AAA, CCC)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 8 input, line 4, column 13: expected a defined node key, found `CCC`"
+        );
+
+        let error = parse_input("RL\nLR\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "an empty line");
+
+        let error = parse_input("RL\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.expected, "a new node key");
+    }
+
+    #[test]
+    fn test_missing_node() {
+        // Only part one needs `AAA` and `ZZZ`
+        assert_eq!(
+            part_one(SAMPLE_INPUT_3).unwrap_err().to_string(),
+            "day 8 input, line 11, column 1: expected a node `AAA`, found nothing"
+        );
+        let parsed = Eight::parse(SAMPLE_INPUT_3).unwrap();
+        assert_eq!(Eight::part_two(&parsed), Some(Ok(6.into())));
+
+        let error = part_one("R\n\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (4, "a node `ZZZ`"));
     }
 }
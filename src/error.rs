@@ -0,0 +1,138 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// Error produced when a puzzle input does not match the expected format.
+///
+/// Lines and columns are 1-based and columns count characters, so they can be
+/// used to point directly into the input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Error on the first line at `column`, see [`ParseError::on_line`] and
+    /// [`ParseError::shift_column`] to move it to its place in the input.
+    pub fn new(day: u8, column: usize, expected: impl Into<String>, found: &str) -> Self {
+        Self {
+            day,
+            line: 1,
+            column,
+            expected: expected.into(),
+            found: found.to_string(),
+        }
+    }
+
+    /// Error pointing at `part`, which must be a slice of `source`.
+    pub fn at(day: u8, source: &str, part: &str, expected: impl Into<String>) -> Self {
+        Self::new(day, column_of(source, part), expected, part)
+    }
+
+    /// Moves an error of an item starting on the 1-based `line` of the input.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line += line - 1;
+        self
+    }
+
+    /// Moves an error of a fragment starting at the 1-based `column` of its line.
+    pub fn shift_column(mut self, column: usize) -> Self {
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} input, line {}, column {}: expected {}, found ",
+            self.day, self.line, self.column, self.expected
+        )?;
+
+        if self.found.is_empty() {
+            f.write_str("nothing")
+        } else {
+            write!(f, "`{}`", self.found)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Empty slice at the end of `part`, used to report missing input after it.
+pub fn end_of(part: &str) -> &str {
+    &part[part.len()..]
+}
+
+/// Parses every line of the input, reporting errors on their line.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| line.parse().map_err(|e: ParseError| e.on_line(idx + 1)))
+        .collect()
+}
+
+/// 1-based line of `part` within `source`.
+///
+/// `part` must be a slice of `source`, see [`column_of`].
+pub fn line_of(source: &str, part: &str) -> usize {
+    source[..offset_of(source, part)].matches('\n').count() + 1
+}
+
+/// 1-based character column of `part` within `source`.
+///
+/// `part` must be a slice of `source`, anything else points past its end.
+pub fn column_of(source: &str, part: &str) -> usize {
+    source[..offset_of(source, part)].chars().count() + 1
+}
+
+fn offset_of(source: &str, part: &str) -> usize {
+    (part.as_ptr() as usize)
+        .checked_sub(source.as_ptr() as usize)
+        .filter(|offset| *offset <= source.len())
+        .unwrap_or(source.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "Game 1: 3 blue";
+        let (_, sets) = line.split_once(':').unwrap();
+
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, sets), 8);
+        assert_eq!(column_of(line, end_of(line)), 15);
+    }
+
+    #[test]
+    fn test_line_of() {
+        let input = "seeds: 1\n\nmap:\n1 2 3";
+        let block = input.split("\n\n").nth(1).unwrap();
+
+        assert_eq!(line_of(input, input), 1);
+        assert_eq!(line_of(input, block), 3);
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "Game 1: 3 yellow";
+        let error = ParseError::at(2, line, &line[10..], "a cube colour").on_line(4);
+
+        assert_eq!(
+            error.to_string(),
+            "day 2 input, line 4, column 11: expected a cube colour, found `yellow`"
+        );
+    }
+}
@@ -1,13 +1,48 @@
-use std::ops::Range;
+use std::{ops::Range, str::FromStr};
 
-fn part_one(input: &str) -> usize {
-    let (seeds, maps) = parse_input(input);
+use crate::{
+    error::{end_of, line_of, ParseError},
+    solver::{Answer, Solver},
+};
 
+const DAY: u8 = 5;
+
+pub struct Five;
+
+impl Solver for Five {
+    type Parsed = (Vec<usize>, Vec<Map>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one((seeds, maps): &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(seeds, maps).into())
+    }
+
+    fn part_two((seeds, maps): &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(seeds, maps).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let (seeds, maps) = parse_input(input)?;
+
+    Ok(solve_one(&seeds, &maps))
+}
+
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let (seed_ranges, maps) = parse_input(input)?;
+
+    Ok(solve_two(&seed_ranges, &maps))
+}
+
+fn solve_one(seeds: &[usize], maps: &[Map]) -> usize {
     seeds
         .iter()
         .map(|seed| {
             let mut source = *seed;
-            for map in &maps {
+            for map in maps {
                 source = map.to_destination(source);
             }
 
@@ -17,9 +52,7 @@ fn part_one(input: &str) -> usize {
         .unwrap()
 }
 
-fn part_two(input: &str) -> usize {
-    let (seed_ranges, maps) = parse_input(input);
-
+fn solve_two(seed_ranges: &[usize], maps: &[Map]) -> usize {
     let ranges: Vec<Range<usize>> = seed_ranges
         .chunks(2)
         .map(|item| item[0]..item[0] + item[1])
@@ -27,13 +60,13 @@ fn part_two(input: &str) -> usize {
 
     for lowest in 0..usize::MAX {
         let mut destination = lowest;
-        for map in maps.iter().clone().rev() {
+        for map in maps.iter().rev() {
             destination = map.to_source(destination);
         }
 
         for range in &ranges {
             if range.contains(&destination) {
-                return destination;
+                return lowest;
             }
         }
     }
@@ -41,7 +74,8 @@ fn part_two(input: &str) -> usize {
     unreachable!()
 }
 
-fn process_seed_range(seeds: &Range<usize>, maps: &[Map]) -> usize {
+/// Lowest location of any seed in the range.
+pub fn process_seed_range(seeds: &Range<usize>, maps: &[Map]) -> usize {
     seeds
         .clone()
         .map(|seed| {
@@ -56,24 +90,51 @@ fn process_seed_range(seeds: &Range<usize>, maps: &[Map]) -> usize {
         .unwrap()
 }
 
-fn parse_input(input: &str) -> (Vec<usize>, Vec<Map>) {
+pub fn parse_input(input: &str) -> Result<(Vec<usize>, Vec<Map>), ParseError> {
     let mut lines = input.split("\n\n");
 
     // Seeds
-    let seeds = lines.next().unwrap().split(':').last().unwrap();
-    let seeds = seeds
+    let line = lines.next().unwrap_or_default();
+    let (label, seeds) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::at(DAY, line, end_of(line), "`seeds:` followed by numbers"))?;
+    if label != "seeds" {
+        return Err(ParseError::at(DAY, line, label, "`seeds:`"));
+    }
+    let seeds: Vec<usize> = seeds
         .split_ascii_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ParseError::at(DAY, line, s, "a seed number"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Part two reads the seeds as pairs of range start and length
+    if seeds.is_empty() {
+        return Err(ParseError::at(DAY, line, end_of(line), "a seed number"));
+    }
+    if !seeds.len().is_multiple_of(2) {
+        return Err(ParseError::at(
+            DAY,
+            line,
+            end_of(line),
+            "a seed range length",
+        ));
+    }
 
     // Maps
-    let maps = lines.map(Map::from).collect();
+    let maps = lines
+        .map(|map| {
+            map.parse()
+                .map_err(|e: ParseError| e.on_line(line_of(input, map)))
+        })
+        .collect::<Result<_, _>>()?;
 
-    (seeds, maps)
+    Ok((seeds, maps))
 }
 
 #[derive(Debug)]
-struct Map(Vec<Instruction>);
+pub struct Map(pub Vec<Instruction>);
 
 impl Map {
     pub fn to_destination(&self, value: usize) -> usize {
@@ -97,18 +158,26 @@ impl Map {
     }
 }
 
-impl From<&str> for Map {
-    fn from(value: &str) -> Self {
-        let instructions = value.lines().skip(1).map(Instruction::from).collect();
-        Self(instructions)
+impl FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let instructions = value
+            .lines()
+            .enumerate()
+            .skip(1)
+            .map(|(idx, line)| line.parse().map_err(|e: ParseError| e.on_line(idx + 1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self(instructions))
     }
 }
 
 #[derive(Debug)]
-struct Instruction {
-    source: usize,
-    destination: usize,
-    step: usize,
+pub struct Instruction {
+    pub source: usize,
+    pub destination: usize,
+    pub step: usize,
 }
 
 impl Instruction {
@@ -135,27 +204,36 @@ impl Instruction {
         Some(self.source + dif)
     }
 }
-impl From<&str> for Instruction {
-    fn from(value: &str) -> Self {
-        let mut split = value.split_ascii_whitespace();
-
-        let destination = split.next().unwrap().parse().unwrap();
-        let source = split.next().unwrap().parse().unwrap();
-        let step = split.next().unwrap().parse().unwrap();
+impl FromStr for Instruction {
+    type Err = ParseError;
 
-        Self {
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut split = value.split_ascii_whitespace();
+        let mut next = |expected: &str| -> Result<usize, ParseError> {
+            let number = split
+                .next()
+                .ok_or_else(|| ParseError::at(DAY, value, end_of(value), expected))?;
+            number
+                .parse()
+                .map_err(|_| ParseError::at(DAY, value, number, expected))
+        };
+
+        let destination = next("a destination range start")?;
+        let source = next("a source range start")?;
+        let step = next("a range length")?;
+
+        Ok(Self {
             source,
             destination,
             step,
-        }
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/five.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     const SAMPLE_INPUT: &str = "seeds: 79 14 55 13
 
@@ -193,23 +271,49 @@ humidity-to-location map:
 
     #[test]
     fn test_one() {
-        assert_eq!(part_one(SAMPLE_INPUT), 35);
+        assert_eq!(part_one(SAMPLE_INPUT).unwrap(), 35);
     }
 
     #[test]
     fn solution_one() {
-        let res = part_one(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("five") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("five", Part::One));
     }
 
     #[test]
     fn test_two() {
-        assert_eq!(part_two(SAMPLE_INPUT), 46);
+        assert_eq!(part_two(SAMPLE_INPUT).unwrap(), 46);
     }
 
     #[test]
     fn solution_two() {
-        let res = part_two(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("five") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("five", Part::Two));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let input = SAMPLE_INPUT.replace("37 52 2", "37 52");
+
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!((error.line, error.column), (9, 6));
+        assert_eq!(error.expected, "a range length");
+
+        let seeds = |line: &str| {
+            let error = parse_input(&SAMPLE_INPUT.replace("seeds: 79 14 55 13", line)).unwrap_err();
+            (error.line, error.column, error.expected)
+        };
+        assert_eq!(seeds("seeds:"), (1, 7, "a seed number".to_string()));
+        assert_eq!(
+            seeds("seeds: 79 14 55"),
+            (1, 16, "a seed range length".to_string())
+        );
+        assert_eq!(seeds("soils: 79 14"), (1, 1, "`seeds:`".to_string()));
     }
 }
@@ -1,16 +1,67 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
-fn part_one(input: &str) -> i32 {
-    input.lines().map(Card::from).map(|c| c.score()).sum()
+use crate::{
+    error::{end_of, parse_lines, ParseError},
+    solver::{Answer, Solver},
+};
+
+const DAY: u8 = 4;
+
+pub struct Four;
+
+impl Solver for Four {
+    type Parsed = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(cards: &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(cards).into())
+    }
+
+    fn part_two(cards: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(cards).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_one(&parse_input(input)?))
+}
+
+pub fn part_two(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_two(&parse_input(input)?))
 }
 
-fn part_two(input: &str) -> i32 {
-    let cards = input.lines().map(Card::from);
-    let cards_count = cards.clone().count();
+/// Parses the cards, which must be numbered from 1 in order.
+pub fn parse_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    let cards: Vec<Card> = parse_lines(input)?;
+
+    for ((idx, card), line) in cards.iter().enumerate().zip(input.lines()) {
+        let expected = idx as i32 + 1;
+        if card.id != expected {
+            let (header, _) = line.split_once(':').unwrap_or_default();
+            let id = header
+                .split_ascii_whitespace()
+                .next_back()
+                .unwrap_or(header);
+            let error = ParseError::at(DAY, line, id, format!("card id {expected}"));
+            return Err(error.on_line(idx + 1));
+        }
+    }
+
+    Ok(cards)
+}
+
+fn solve_one(cards: &[Card]) -> i32 {
+    cards.iter().map(|c| c.score()).sum()
+}
+
+fn solve_two(cards: &[Card]) -> i32 {
     let mut instances = HashMap::new();
 
     // Fill instances
-    cards.clone().for_each(|c| {
+    cards.iter().for_each(|c| {
         instances.insert(c.id, 1);
     });
 
@@ -19,11 +70,10 @@ fn part_two(input: &str) -> i32 {
         let matching_count = card.matching_numbers();
 
         for idx in 1..=matching_count {
-            if idx as usize >= cards_count {
+            // Cards are never copied past the end of the table
+            let Some(next_instance) = instances.get_mut(&(card.id + idx)) else {
                 break;
-            }
-            let id = card.id + idx;
-            let next_instance = instances.get_mut(&id).unwrap();
+            };
             *next_instance += count;
         }
     }
@@ -31,10 +81,11 @@ fn part_two(input: &str) -> i32 {
     instances.values().sum()
 }
 
-struct Card {
-    id: i32,
-    winning_numbers: Vec<i32>,
-    scratched_numbers: Vec<i32>,
+#[derive(Debug)]
+pub struct Card {
+    pub id: i32,
+    pub winning_numbers: Vec<i32>,
+    pub scratched_numbers: Vec<i32>,
 }
 
 impl Card {
@@ -69,48 +120,51 @@ impl Card {
     }
 }
 
-impl From<&str> for Card {
-    fn from(value: &str) -> Self {
-        let mut split = value.split(':');
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (header, numbers) = value
+            .split_once(':')
+            .ok_or_else(|| ParseError::at(DAY, value, end_of(value), "`:` after the card id"))?;
 
-        let id: i32 = split
-            .next()
-            .unwrap()
+        let id = header
             .split_ascii_whitespace()
-            .last()
-            .unwrap()
+            .next_back()
+            .ok_or_else(|| ParseError::at(DAY, value, header, "a card id"))?;
+        let id: i32 = id
             .parse()
-            .unwrap();
+            .map_err(|_| ParseError::at(DAY, value, id, "a card id"))?;
 
-        let mut numbers = split.next().unwrap().split('|');
+        let (winning_numbers, scratched_numbers) = numbers
+            .split_once('|')
+            .ok_or_else(|| ParseError::at(DAY, value, end_of(value), "`|` between the numbers"))?;
 
-        let winning_numbers: Vec<i32> = numbers
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        let scratched_numbers: Vec<i32> = numbers
-            .next()
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
+        let winning_numbers = parse_numbers(value, winning_numbers)?;
+        let scratched_numbers = parse_numbers(value, scratched_numbers)?;
 
-        Self {
+        Ok(Self {
             id,
             winning_numbers,
             scratched_numbers,
-        }
+        })
     }
 }
 
+fn parse_numbers(line: &str, numbers: &str) -> Result<Vec<i32>, ParseError> {
+    numbers
+        .split_ascii_whitespace()
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ParseError::at(DAY, line, s, "a number"))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/four.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     #[test]
     fn test_one() {
@@ -121,13 +175,16 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        assert_eq!(part_one(input), 13)
+        assert_eq!(part_one(input).unwrap(), 13)
     }
 
     #[test]
     fn solution_one() {
-        let res = part_one(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("four") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("four", Part::One));
     }
 
     #[test]
@@ -139,12 +196,31 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        assert_eq!(part_two(input), 30)
+        assert_eq!(part_two(input).unwrap(), 30)
     }
 
     #[test]
     fn solution_two() {
-        let res = part_two(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("four") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("four", Part::Two));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = parse_input("Card 1: 41 48 | 83 x6").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 20));
+        assert_eq!(error.expected, "a number");
+
+        let error = parse_input("Card 1: 1 | 1\nCard  3: 1 | 2").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 4 input, line 2, column 7: expected card id 2, found `3`"
+        );
+
+        // The last card wins copies past the end of the table
+        assert_eq!(part_two("Card 1: 1 | 2\nCard 2: 1 2 | 1 2").unwrap(), 2);
     }
 }
@@ -0,0 +1,517 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut, Range},
+    str::FromStr,
+};
+
+/// Position within a [`Grid`], `x` is the column and `y` the row.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Offsets of the eight surrounding cells, row by row.
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Offsets of the four orthogonally adjacent cells.
+const ORTHOGONAL_NEIGHBOURS: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Which cells around a point are adjacent to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The four cells sharing an edge.
+    Orthogonal,
+    /// The eight surrounding cells, diagonals included.
+    #[default]
+    Moore,
+    /// Every cell at most this many rows and columns away.
+    Radius(usize),
+}
+
+impl Neighbourhood {
+    /// Largest row or column distance of an adjacent cell.
+    fn radius(&self) -> usize {
+        match self {
+            Neighbourhood::Orthogonal | Neighbourhood::Moore => 1,
+            &Neighbourhood::Radius(radius) => radius,
+        }
+    }
+
+    fn contains(&self, dx: isize, dy: isize) -> bool {
+        let distance = dx.unsigned_abs().max(dy.unsigned_abs());
+
+        match self {
+            Neighbourhood::Orthogonal => dx.unsigned_abs() + dy.unsigned_abs() == 1,
+            _ => distance != 0 && distance <= self.radius(),
+        }
+    }
+
+    /// Offsets at most `dx` columns and `dy` rows away, row by row.
+    fn offsets(self, dx: usize, dy: usize) -> impl Iterator<Item = (isize, isize)> {
+        let (dx, dy) = (dx as isize, dy as isize);
+
+        (-dy..=dy)
+            .flat_map(move |y| (-dx..=dx).map(move |x| (x, y)))
+            .filter(move |&(x, y)| self.contains(x, y))
+    }
+}
+
+/// Neighbourhood and whether it wraps around the edges of the grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Adjacency {
+    pub neighbourhood: Neighbourhood,
+    /// Treat the grid as a torus, the last column is next to the first.
+    pub toroidal: bool,
+}
+
+impl fmt::Display for Adjacency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.neighbourhood {
+            Neighbourhood::Orthogonal => write!(f, "4")?,
+            Neighbourhood::Moore => write!(f, "8")?,
+            Neighbourhood::Radius(radius) => write!(f, "radius-{radius}")?,
+        }
+        if self.toroidal {
+            write!(f, "-toroidal")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses `4`, `8` or `radius-N`, optionally followed by `-toroidal`.
+impl FromStr for Adjacency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (neighbourhood, toroidal) = match s.strip_suffix("-toroidal") {
+            Some(neighbourhood) => (neighbourhood, true),
+            None => (s, false),
+        };
+
+        let neighbourhood = match neighbourhood {
+            "4" => Neighbourhood::Orthogonal,
+            "8" => Neighbourhood::Moore,
+            _ => neighbourhood
+                .strip_prefix("radius-")
+                .and_then(|radius| radius.parse().ok())
+                .map(Neighbourhood::Radius)
+                .ok_or_else(|| {
+                    format!(
+                        "expected `4`, `8` or `radius-N` with optional `-toroidal`, found `{s}`"
+                    )
+                })?,
+        };
+
+        Ok(Self {
+            neighbourhood,
+            toroidal,
+        })
+    }
+}
+
+/// Dense two dimensional grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.contains(point)
+            .then(|| &mut self.cells[point.y * self.width + point.x])
+    }
+
+    /// Point moved by the offset, `None` outside of the grid.
+    pub fn offset(&self, point: Point, dx: isize, dy: isize) -> Option<Point> {
+        let point = Point::new(
+            point.x.checked_add_signed(dx)?,
+            point.y.checked_add_signed(dy)?,
+        );
+
+        self.contains(point).then_some(point)
+    }
+
+    /// Cells at the offsets from the point that are within the grid.
+    pub fn cells_at<'a>(
+        &'a self,
+        point: Point,
+        offsets: impl IntoIterator<Item = (isize, isize)> + 'a,
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        offsets
+            .into_iter()
+            .filter_map(move |(dx, dy)| self.offset(point, dx, dy))
+            .map(|p| (p, &self[p]))
+    }
+
+    /// The up to eight surrounding cells, diagonals included.
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.cells_at(point, NEIGHBOURS)
+    }
+
+    /// The up to four cells sharing an edge with the point.
+    pub fn orthogonal_neighbours(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.cells_at(point, ORTHOGONAL_NEIGHBOURS)
+    }
+
+    /// Cells adjacent to the point, each listed once and never the point
+    /// itself, even when a small toroidal grid wraps onto them again.
+    pub fn adjacent(
+        &self,
+        point: Point,
+        adjacency: Adjacency,
+    ) -> impl Iterator<Item = (Point, &T)> {
+        let neighbourhood = adjacency.neighbourhood;
+        // Offsets further than the size of the grid reach no other cells
+        let rx = neighbourhood.radius().min(self.width);
+        let ry = neighbourhood.radius().min(self.height);
+
+        neighbourhood
+            .offsets(rx, ry)
+            .filter_map(move |(dx, dy)| {
+                if !adjacency.toroidal {
+                    return self.offset(point, dx, dy);
+                }
+                if self.cells.is_empty() || self.wraps_onto_earlier(neighbourhood, (rx, ry), dx, dy)
+                {
+                    return None;
+                }
+
+                let wrap = |value: usize, delta: isize, size: usize| {
+                    (value as isize + delta).rem_euclid(size as isize) as usize
+                };
+                Some(Point::new(
+                    wrap(point.x, dx, self.width),
+                    wrap(point.y, dy, self.height),
+                ))
+            })
+            .filter(move |&p| p != point)
+            .map(|p| (p, &self[p]))
+    }
+
+    /// Whether an offset before `(dx, dy)` in row order wraps onto the same
+    /// cell, offsets are congruent when they differ by multiples of the size.
+    fn wraps_onto_earlier(
+        &self,
+        neighbourhood: Neighbourhood,
+        (rx, ry): (usize, usize),
+        dx: isize,
+        dy: isize,
+    ) -> bool {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (rx, ry) = (rx as isize, ry as isize);
+        // Smallest offset congruent to `value` within the radius
+        let first =
+            |value: isize, radius: isize, size: isize| -radius + (value + radius).rem_euclid(size);
+
+        (first(dy, ry, height)..=dy).step_by(self.height).any(|y| {
+            let last = if y == dy { dx - 1 } else { rx };
+            (first(dx, rx, width)..=last)
+                .step_by(self.width)
+                .any(|x| neighbourhood.contains(x, y))
+        })
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(idx, cell)| (Point::new(idx % width, idx / width), cell))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks_exact` panics on a zero width
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} outside of the grid");
+
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Rectangular view of the cells in the ranges, clamped to the grid.
+    pub fn region(&self, x: Range<usize>, y: Range<usize>) -> Region<'_, T> {
+        let clamp = |range: Range<usize>, max: usize| range.start.min(max)..range.end.min(max);
+
+        Region {
+            grid: self,
+            x: clamp(x, self.width),
+            y: clamp(y, self.height),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point} outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point} outside of the grid"))
+    }
+}
+
+/// Rectangular part of a [`Grid`], see [`Grid::region`].
+#[derive(Debug, Clone)]
+pub struct Region<'a, T> {
+    grid: &'a Grid<T>,
+    x: Range<usize>,
+    y: Range<usize>,
+}
+
+impl<'a, T> Region<'a, T> {
+    pub fn width(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.y.len()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        self.y.clone().map(|y| &self.grid.row(y)[self.x.clone()])
+    }
+
+    /// Cells of the region with their position in the grid.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> + '_ {
+        self.y.clone().flat_map(move |y| {
+            self.x.clone().map(move |x| {
+                let point = Point::new(x, y);
+                (point, &self.grid[point])
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 4x3 grid holding `10 * y + x`.
+    fn grid() -> Grid<usize> {
+        Grid::from_fn(4, 3, |p| 10 * p.y + p.x)
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = grid();
+
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid[Point::new(3, 2)], 23);
+        assert_eq!(grid.get(Point::new(4, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+
+        grid[Point::new(1, 1)] = 99;
+        assert_eq!(grid.row(1), [10, 99, 12, 13]);
+        assert_eq!(Grid::new(2, 1, 'x').row(0), ['x', 'x']);
+    }
+
+    #[test]
+    #[should_panic(expected = "(4, 0) outside of the grid")]
+    fn test_index_out_of_bounds() {
+        let _ = grid()[Point::new(4, 0)];
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+
+        let corner: Vec<usize> = grid.neighbours(Point::new(0, 0)).map(|(_, &c)| c).collect();
+        assert_eq!(corner, [1, 10, 11]);
+
+        let inner: Vec<usize> = grid.neighbours(Point::new(1, 1)).map(|(_, &c)| c).collect();
+        assert_eq!(inner, [0, 1, 2, 10, 12, 20, 21, 22]);
+
+        let edge: Vec<Point> = grid
+            .orthogonal_neighbours(Point::new(3, 1))
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(edge, [Point::new(3, 0), Point::new(2, 1), Point::new(3, 2)]);
+    }
+
+    #[test]
+    fn test_adjacent() {
+        let grid = grid();
+        let adjacent = |point: Point, adjacency: &str| -> Vec<usize> {
+            let mut cells: Vec<usize> = grid
+                .adjacent(point, adjacency.parse().unwrap())
+                .map(|(_, &c)| c)
+                .collect();
+            cells.sort();
+            cells
+        };
+
+        assert_eq!(adjacent(Point::new(0, 0), "4"), [1, 10]);
+        assert_eq!(adjacent(Point::new(0, 0), "8"), [1, 10, 11]);
+        assert_eq!(
+            adjacent(Point::new(0, 0), "radius-2"),
+            [1, 2, 10, 11, 12, 20, 21, 22]
+        );
+        assert_eq!(adjacent(Point::new(0, 0), "4-toroidal"), [1, 3, 10, 20]);
+        assert_eq!(
+            adjacent(Point::new(0, 0), "8-toroidal"),
+            [1, 3, 10, 11, 13, 20, 21, 23]
+        );
+
+        // Wrapping a radius of two around three rows reaches every row once
+        assert_eq!(adjacent(Point::new(0, 1), "radius-2-toroidal").len(), 11);
+        // Radii beyond the grid reach every cell without enumerating the square
+        assert_eq!(adjacent(Point::new(0, 1), "radius-1000000000").len(), 11);
+        assert_eq!(
+            adjacent(Point::new(0, 1), "radius-1000000000-toroidal").len(),
+            11
+        );
+    }
+
+    #[test]
+    fn test_adjacent_small_toroidal() {
+        // Every cell within the neighbourhood, wrapped and deduplicated
+        let expected = |grid: &Grid<()>, point: Point, adjacency: Adjacency| {
+            let radius = match adjacency.neighbourhood {
+                Neighbourhood::Radius(radius) => radius as isize,
+                _ => 1,
+            };
+            let mut cells: Vec<Point> = (-radius..=radius)
+                .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| adjacency.neighbourhood.contains(dx, dy))
+                .map(|(dx, dy)| {
+                    Point::new(
+                        (point.x as isize + dx).rem_euclid(grid.width() as isize) as usize,
+                        (point.y as isize + dy).rem_euclid(grid.height() as isize) as usize,
+                    )
+                })
+                .filter(|&p| p != point)
+                .collect();
+            cells.sort();
+            cells.dedup();
+            cells
+        };
+
+        for (width, height) in [(1, 1), (1, 3), (2, 2), (2, 5), (3, 4), (5, 5)] {
+            let grid = Grid::new(width, height, ());
+            for adjacency in ["4", "8", "radius-0", "radius-2", "radius-3", "radius-6"] {
+                let adjacency: Adjacency = format!("{adjacency}-toroidal").parse().unwrap();
+                for (point, _) in grid.iter() {
+                    let mut cells: Vec<Point> =
+                        grid.adjacent(point, adjacency).map(|(p, _)| p).collect();
+                    cells.sort();
+
+                    assert_eq!(
+                        cells,
+                        expected(&grid, point, adjacency),
+                        "{width}x{height} {adjacency} {point}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_adjacency() {
+        for adjacency in ["4", "8", "radius-3", "8-toroidal", "radius-1-toroidal"] {
+            assert_eq!(
+                adjacency.parse::<Adjacency>().unwrap().to_string(),
+                adjacency
+            );
+        }
+        assert_eq!("8".parse::<Adjacency>().unwrap(), Adjacency::default());
+        assert!("6".parse::<Adjacency>().is_err());
+        assert!("radius-x".parse::<Adjacency>().is_err());
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = grid();
+
+        let column: Vec<usize> = grid.column(2).copied().collect();
+        assert_eq!(column, [2, 12, 22]);
+        assert_eq!(grid.rows().count(), 3);
+
+        let region = grid.region(2..10, 1..3);
+        assert_eq!((region.width(), region.height()), (2, 2));
+        let rows: Vec<&[usize]> = region.rows().collect();
+        assert_eq!(rows, [&[12, 13][..], &[22, 23][..]]);
+        assert_eq!(region.iter().next(), Some((Point::new(2, 1), &12)));
+
+        let points: Vec<Point> = grid.iter().map(|(p, _)| p).take(5).collect();
+        assert_eq!(points[4], Point::new(0, 1));
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::solver::Day;
+
+/// Optional configuration file in the working directory.
+pub const CONFIG_PATH: &str = "aoc.toml";
+
+pub(crate) const USER_AGENT: &str =
+    "advent_of_code_2023 (github.com/StrelecJan/advent_of_code_2023)";
+
+/// Where puzzle inputs are cached and where they are downloaded from.
+///
+/// Values from [`CONFIG_PATH`] are overridden by the `AOC_CACHE_DIR`,
+/// `AOC_SESSION` and `AOC_BASE_URL` environment variables.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub cache_dir: PathBuf,
+    /// Value of the `session` cookie of a logged in Advent of Code account.
+    pub session: Option<String>,
+    pub base_url: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cache_dir: PathBuf::from("assets"),
+            session: None,
+            base_url: "https://adventofcode.com".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self, InputError> {
+        let mut config: Config = match fs::read_to_string(CONFIG_PATH) {
+            Ok(config) => toml::from_str(&config)
+                .map_err(|err| InputError::Config(format!("{CONFIG_PATH}: {err}")))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(err) => return Err(InputError::Io(PathBuf::from(CONFIG_PATH), err)),
+        };
+
+        if let Ok(cache_dir) = env::var("AOC_CACHE_DIR") {
+            config.cache_dir = PathBuf::from(cache_dir);
+        }
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    pub fn input_path(&self, day: &Day) -> PathBuf {
+        self.cache_dir.join(format!("{}.txt", day.name))
+    }
+
+    /// Reads the cached input of the day, the empty placeholder created by
+    /// `new` counts as missing.
+    pub fn read(&self, day: &Day) -> Result<String, InputError> {
+        let path = self.input_path(day);
+        let input = read(&path)?;
+        if input.is_empty() {
+            return Err(InputError::Missing(path));
+        }
+
+        Ok(input)
+    }
+
+    /// Downloads the input of the day into the cache unless it is cached
+    /// already, returns the path of the cached input and whether it was
+    /// downloaded.
+    pub fn fetch(&self, day: &Day) -> Result<(PathBuf, bool), InputError> {
+        let path = self.input_path(day);
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+            return Ok((path, false));
+        }
+
+        let session = self.session.as_deref().ok_or(InputError::MissingSession)?;
+        let url = format!(
+            "{}/2023/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            day.number
+        );
+
+        let input = ureq::get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|err| InputError::Http(err.to_string()))?
+            .into_string()
+            .map_err(|err| InputError::Http(format!("{url}: {err}")))?;
+
+        fs::create_dir_all(&self.cache_dir)
+            .map_err(|err| InputError::Io(self.cache_dir.clone(), err))?;
+
+        // Write through a temporary file so an interrupted download is not cached
+        let partial = path.with_extension("txt.partial");
+        fs::write(&partial, input).map_err(|err| InputError::Io(partial.clone(), err))?;
+        fs::rename(&partial, &path).map_err(|err| InputError::Io(path.clone(), err))?;
+
+        Ok((path, true))
+    }
+}
+
+/// Reads an input file, `-` reads from stdin.
+pub fn read(path: &Path) -> Result<String, InputError> {
+    if path == Path::new("-") {
+        return io::read_to_string(io::stdin()).map_err(|err| InputError::Io(path.into(), err));
+    }
+
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => InputError::Missing(path.into()),
+        _ => InputError::Io(path.into(), err),
+    })
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// The input is not cached, see [`Config::fetch`].
+    Missing(PathBuf),
+    MissingSession,
+    Config(String),
+    Io(PathBuf, io::Error),
+    Http(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(
+                f,
+                "input {} is missing, download it with `fetch`",
+                path.display()
+            ),
+            InputError::MissingSession => write!(
+                f,
+                "no session cookie, set `session` in {CONFIG_PATH} or AOC_SESSION"
+            ),
+            InputError::Config(err) => write!(f, "invalid configuration {err}"),
+            InputError::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            InputError::Http(err) => write!(f, "download failed, {err}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Cached input of the day for the `solution_*` tests, `None` skips them.
+#[cfg(test)]
+pub(crate) fn cached_input(name: &str) -> Option<String> {
+    let day = crate::registry::DAYS.iter().find(|d| d.name == name)?;
+
+    Config::load().ok()?.read(day).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        registry,
+        test_util::{config, stub_server},
+    };
+
+    #[test]
+    fn test_fetch() {
+        let (url, server) = stub_server("200 OK", "1abc2\n");
+        let (_cache_dir, config) = config(url);
+        let day = registry::find(1).unwrap();
+
+        let (path, downloaded) = config.fetch(day).unwrap();
+        assert!(downloaded);
+        assert_eq!(path, config.cache_dir.join("one.txt"));
+        assert_eq!(config.read(day).unwrap(), "1abc2\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/1/input HTTP/1.1"));
+        assert!(request.contains("Cookie: session=secret"));
+
+        // The stub is gone, a second request would fail
+        let (_, downloaded) = config.fetch(day).unwrap();
+        assert!(!downloaded);
+    }
+
+    #[test]
+    fn test_fetch_placeholder() {
+        let (url, server) = stub_server("200 OK", "1abc2\n");
+        let (_cache_dir, config) = config(url);
+        let day = registry::find(1).unwrap();
+
+        fs::create_dir_all(&config.cache_dir).unwrap();
+        fs::write(config.input_path(day), "").unwrap();
+        assert!(matches!(config.read(day), Err(InputError::Missing(_))));
+
+        assert!(config.fetch(day).unwrap().1);
+        assert_eq!(config.read(day).unwrap(), "1abc2\n");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let (url, server) = stub_server("400 Bad Request", "expired session");
+        let (_cache_dir, config) = config(url);
+        let day = registry::find(2).unwrap();
+
+        assert!(matches!(config.fetch(day), Err(InputError::Http(_))));
+        assert!(matches!(config.read(day), Err(InputError::Missing(_))));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_fetch_without_session() {
+        let (_cache_dir, mut config) = config("http://127.0.0.1:9".to_string());
+        config.session = None;
+
+        let day = registry::find(3).unwrap();
+        assert!(matches!(config.fetch(day), Err(InputError::MissingSession)));
+    }
+}
@@ -0,0 +1,33 @@
+//! Advent of Code 2023 puzzle solutions.
+//!
+//! Every day exposes its parsed model types, `part_one`/`part_two` entry points
+//! and a [`solver::Solver`] implementation listed in [`registry::DAYS`].
+
+#![deny(clippy::all)]
+
+pub mod bench;
+pub mod eight;
+pub mod error;
+pub mod five;
+pub mod four;
+pub mod grid;
+pub mod input;
+pub mod one;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod seven;
+pub mod seven_one;
+pub mod seven_two;
+pub mod six;
+pub mod solver;
+pub mod submit;
+#[cfg(test)]
+mod test_util;
+pub mod three;
+pub mod three_render;
+pub mod three_report;
+pub mod two;
+pub mod two_inference;
+pub mod two_query;
+pub mod verify;
@@ -1,16 +1,593 @@
 #![deny(clippy::all)]
-#![allow(dead_code)]
-
-mod eight;
-mod five;
-mod four;
-mod one;
-mod seven_one;
-mod seven_two;
-mod six;
-mod three;
-mod two;
-
-fn main() {
-    println!("Hello, world!");
+
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
+    process::ExitCode,
+};
+
+use advent_of_code_2023::{
+    bench,
+    grid::Adjacency,
+    input::{self, Config},
+    one::{self, Policy},
+    registry, runner, scaffold,
+    solver::{Day, Part},
+    submit::{self, Verdict},
+    three,
+    three_render::{self, Style},
+    three_report,
+    two::{self, Bag},
+    two_inference,
+    two_query::{self, Filter, Value},
+    verify::{self, Manifest},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve one part of a day and print the answer
+    Run {
+        /// Day of the puzzle (1-25)
+        #[arg(required_unless_present = "all")]
+        day: Option<u8>,
+        /// Part of the puzzle (1 or 2)
+        #[arg(required_unless_present = "all")]
+        part: Option<Part>,
+        #[command(flatten)]
+        input: InputArg,
+        /// Solve every part of every day and print a summary
+        #[arg(long, conflicts_with_all = ["day", "part", "input"])]
+        all: bool,
+        /// Solve the days one after another for accurate timings
+        #[arg(long, requires = "all")]
+        sequential: bool,
+    },
+    /// Check the answers of every day against the expected answers
+    Verify {
+        /// Expected answers manifest
+        #[arg(long, default_value = verify::MANIFEST_PATH)]
+        answers: String,
+        /// Store answers that are missing from the manifest
+        #[arg(long)]
+        record: bool,
+    },
+    /// Download the inputs of days into the input cache
+    Fetch {
+        /// Days to download
+        #[arg(required = true)]
+        days: Vec<u8>,
+    },
+    /// Time parsing and solving of days with an available input
+    Bench {
+        /// Days to benchmark, all days by default
+        days: Vec<u8>,
+        /// Number of runs of every stage
+        #[arg(short, long, default_value_t = 10)]
+        runs: usize,
+        #[arg(short, long, value_enum, default_value_t = Format::Markdown)]
+        format: Format,
+    },
+    /// Sum the day 1 calibration values, reporting lines without a digit
+    Calibrate {
+        /// Part of the puzzle (1 or 2)
+        #[arg(default_value_t = Part::One)]
+        part: Part,
+        /// What to do with a line without a digit
+        #[arg(short, long, value_enum, default_value_t = CalibrationPolicy::Abort)]
+        policy: CalibrationPolicy,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// List which day 2 games are possible with a bag of cubes
+    Bag {
+        /// Bag configuration file with a count of every colour, e.g. `red = 12`
+        #[arg(short, long)]
+        config: Option<String>,
+        /// Number of cubes of a colour, e.g. `yellow=3`
+        #[arg(long = "cubes", value_parser = parse_cubes)]
+        cubes: Vec<(String, i32)>,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// Convert day 2 games to JSON, or JSON back to the puzzle format
+    Games {
+        /// Read JSON and print the games in the puzzle format
+        #[arg(long)]
+        from_json: bool,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// Estimate the bag composition of every day 2 game
+    Infer {
+        /// Cubes of each colour considered on top of the minimum bag
+        #[arg(long, default_value_t = two_inference::Options::default().extra)]
+        extra: i32,
+        /// Confidence level of the ranges
+        #[arg(long, default_value_t = two_inference::Options::default().confidence)]
+        confidence: f64,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// Filter day 2 games with an expression and aggregate values over them
+    Query {
+        /// Condition games must meet, e.g. `max(blue) > 10 and count(sets) >= 3`
+        filter: String,
+        /// Value summed, averaged and bounded over the matching games, e.g. `avg(red)`
+        #[arg(short, long = "aggregate")]
+        aggregates: Vec<String>,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// List the symbols adjacent to every number of the day 3 schematic
+    Attribute {
+        /// Cells counting as adjacent: `4`, `8` or `radius-N`, optionally `-toroidal`
+        #[arg(short, long, default_value_t = Adjacency::default())]
+        adjacency: Adjacency,
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// Print the day 3 schematic with part numbers, symbols and gears highlighted
+    Render {
+        /// Cells counting as adjacent: `4`, `8` or `radius-N`, optionally `-toroidal`
+        #[arg(short, long, default_value_t = Adjacency::default())]
+        adjacency: Adjacency,
+        /// Colours on a terminal, marker lines otherwise
+        #[arg(short, long, value_enum, default_value_t = RenderStyle::Auto)]
+        style: RenderStyle,
+        #[command(flatten)]
+        input: InputArg,
+    },
+    /// Create the module, registration and input placeholder of a new day
+    New {
+        /// Day of the puzzle (1-25)
+        day: u8,
+    },
+    /// Submit the answer of one part of a day
+    Submit {
+        /// Day of the puzzle (1-25)
+        day: u8,
+        /// Part of the puzzle (1 or 2)
+        part: Part,
+        #[command(flatten)]
+        input: InputArg,
+    },
+}
+
+#[derive(Debug, Args)]
+struct InputArg {
+    /// Input file, `-` reads from stdin. Defaults to the day's asset
+    #[arg(id = "input", short, long = "input", value_name = "INPUT")]
+    path: Option<String>,
+}
+
+impl InputArg {
+    /// Reads the input file, or the cached input of the day without one.
+    fn load(&self, day: u8) -> Result<String, String> {
+        match &self.path {
+            Some(path) => input::read(Path::new(path)),
+            None => config()?.read(find_day(day)?),
+        }
+        .map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CalibrationPolicy {
+    Abort,
+    Skip,
+    Zero,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RenderStyle {
+    Auto,
+    Ansi,
+    Plain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Run {
+            all: true,
+            sequential,
+            ..
+        } => run_all(!sequential),
+        Command::Run {
+            day, part, input, ..
+        } => run(day.unwrap(), part.unwrap(), &input),
+        Command::Verify { answers, record } => verify(&answers, record),
+        Command::Bench { days, runs, format } => bench(&days, runs, format),
+        Command::Fetch { days } => fetch(&days),
+        Command::Calibrate {
+            part,
+            policy,
+            input,
+        } => calibrate(part, policy, &input),
+        Command::Bag {
+            config,
+            cubes,
+            input,
+        } => bag(config.as_deref(), &cubes, &input),
+        Command::Games { from_json, input } => games(from_json, &input),
+        Command::Infer {
+            extra,
+            confidence,
+            input,
+        } => infer(two_inference::Options { extra, confidence }, &input),
+        Command::Query {
+            filter,
+            aggregates,
+            input,
+        } => query(&filter, &aggregates, &input),
+        Command::Attribute {
+            adjacency,
+            format,
+            input,
+        } => attribute(adjacency, format, &input),
+        Command::Render {
+            adjacency,
+            style,
+            input,
+        } => render(adjacency, style, &input),
+        Command::New { day } => new(day),
+        Command::Submit { day, part, input } => submit(day, part, &input),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(day: u8, part: Part, input: &InputArg) -> Result<(), String> {
+    let day = find_day(day)?;
+
+    let input = input.load(day.number)?;
+
+    let answer = day
+        .run(&input, part)
+        .map_err(|err| err.to_string())?
+        .ok_or(format!("day {} part {part} is not implemented", day.number))?;
+    println!("{answer}");
+
+    Ok(())
+}
+
+fn run_all(parallel: bool) -> Result<(), String> {
+    let config = config()?;
+    let summary = runner::run_all(registry::DAYS, |day| config.read(day).ok(), parallel);
+    println!("{summary}");
+
+    if !summary.is_success() {
+        return Err("some days failed".to_string());
+    }
+
+    Ok(())
+}
+
+fn verify(path: &str, record: bool) -> Result<(), String> {
+    let manifest =
+        fs::read_to_string(path).map_err(|err| format!("failed to read {path}: {err}"))?;
+    let mut manifest: Manifest = manifest
+        .parse()
+        .map_err(|err| format!("failed to parse {path}: {err}"))?;
+
+    let config = config()?;
+    let report = verify::verify(&manifest, |day| config.read(day).ok());
+    println!("{report}");
+
+    if record {
+        manifest.record(&report);
+        fs::write(path, manifest.to_string())
+            .map_err(|err| format!("failed to write {path}: {err}"))?;
+    }
+
+    if !report.is_success() {
+        let failed: Vec<String> = report
+            .failures()
+            .map(|o| format!("day {} part {}", o.day, o.part))
+            .collect();
+        return Err(format!("answers changed for {}", failed.join(", ")));
+    }
+
+    Ok(())
+}
+
+fn bench(days: &[u8], runs: usize, format: Format) -> Result<(), String> {
+    let days = if days.is_empty() {
+        registry::DAYS.iter().collect()
+    } else {
+        days.iter()
+            .map(|&day| find_day(day))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let config = config()?;
+    let mut report = bench::Report::default();
+    for day in days {
+        let input = match config.read(day) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("skipping day {}: {err}", day.number);
+                continue;
+            }
+        };
+
+        let rows = bench::bench_day(day, &input, runs).map_err(|err| err.to_string())?;
+        report.0.extend(rows);
+    }
+
+    match format {
+        Format::Markdown => print!("{}", report.to_markdown()),
+        Format::Json => println!("{}", report.to_json()),
+    }
+
+    Ok(())
+}
+
+fn fetch(days: &[u8]) -> Result<(), String> {
+    let config = config()?;
+
+    for &day in days {
+        let day = find_day(day)?;
+        let (path, downloaded) = config.fetch(day).map_err(|err| err.to_string())?;
+
+        if downloaded {
+            println!("day {}: downloaded to {}", day.number, path.display());
+        } else {
+            println!("day {}: cached in {}", day.number, path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_cubes(cubes: &str) -> Result<(String, i32), String> {
+    let (colour, count) = cubes
+        .split_once('=')
+        .ok_or("expected `colour=count`".to_string())?;
+    let count = count
+        .parse()
+        .map_err(|_| format!("invalid count `{count}`"))?;
+
+    Ok((colour.to_string(), count))
+}
+
+fn calibrate(part: Part, policy: CalibrationPolicy, input: &InputArg) -> Result<(), String> {
+    let policy = match policy {
+        CalibrationPolicy::Abort => Policy::Abort,
+        CalibrationPolicy::Skip => Policy::Skip,
+        CalibrationPolicy::Zero => Policy::Zero,
+    };
+
+    let input = input.load(1)?;
+
+    let calibration = match part {
+        Part::One => one::part_one_with(&input, policy),
+        Part::Two => one::part_two_with(&input, policy),
+    }
+    .map_err(|err| err.to_string())?;
+
+    for rejected in &calibration.rejected {
+        println!("{rejected}");
+    }
+    println!(
+        "sum {} over {} lines, {} rejected",
+        calibration.sum,
+        calibration.counted,
+        calibration.rejected.len()
+    );
+
+    Ok(())
+}
+
+fn bag(path: Option<&str>, cubes: &[(String, i32)], input: &InputArg) -> Result<(), String> {
+    let mut bag = match path {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {path}: {err}"))?
+            .parse()
+            .map_err(|err| format!("failed to parse {path}: {err}"))?,
+        None => Bag::default(),
+    };
+    for (colour, count) in cubes {
+        bag.set(colour, *count);
+    }
+
+    let input = input.load(2)?;
+    let games = two::parse_input(&input).map_err(|err| err.to_string())?;
+
+    let mut sum = 0;
+    for (game, violation) in two::feasibility(&games, &bag) {
+        match violation {
+            Some(violation) => println!("game {}: impossible, {violation}", game.id),
+            None => {
+                sum += game.id;
+                println!("game {}: possible", game.id);
+            }
+        }
+    }
+    println!("sum of possible game ids: {sum}");
+
+    Ok(())
+}
+
+fn games(from_json: bool, input: &InputArg) -> Result<(), String> {
+    let input = input.load(2)?;
+
+    if from_json {
+        let games = two::from_json(&input).map_err(|err| format!("invalid JSON, {err}"))?;
+        for game in games {
+            println!("{game}");
+        }
+    } else {
+        let games = two::parse_input(&input).map_err(|err| err.to_string())?;
+        println!("{}", two::to_json(&games));
+    }
+
+    Ok(())
+}
+
+fn infer(options: two_inference::Options, input: &InputArg) -> Result<(), String> {
+    if !(0.0..1.0).contains(&options.confidence) {
+        return Err("confidence must be between 0 and 1".to_string());
+    }
+
+    let input = input.load(2)?;
+    let games = two::parse_input(&input).map_err(|err| err.to_string())?;
+
+    for game in &games {
+        let inference = two_inference::infer(game, &options).map_err(|err| err.to_string())?;
+        println!("{inference}");
+    }
+
+    Ok(())
+}
+
+fn query(filter: &str, aggregates: &[String], input: &InputArg) -> Result<(), String> {
+    let filter: Filter = filter
+        .parse()
+        .map_err(|err: two_query::QueryError| err.to_string())?;
+    let values = aggregates
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    let input = input.load(2)?;
+    let games = two::parse_input(&input).map_err(|err| err.to_string())?;
+
+    let (ids, aggregates) = two_query::query(&games, &filter, &values);
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    println!("{} matching games: {}", ids.len(), ids.join(", "));
+
+    for (value, aggregate) in values.iter().zip(aggregates) {
+        match aggregate {
+            Some(a) => println!(
+                "{value}: sum {}, mean {}, min {}, max {}",
+                number(a.sum),
+                number(a.mean),
+                number(a.min),
+                number(a.max)
+            ),
+            None => println!("{value}: no matching games"),
+        }
+    }
+
+    Ok(())
+}
+
+fn attribute(adjacency: Adjacency, format: ReportFormat, input: &InputArg) -> Result<(), String> {
+    let input = input.load(3)?;
+
+    let (symbols, values) = three::parse_with(&input, adjacency);
+    let report = three_report::attribute(&symbols, &values, adjacency);
+
+    match format {
+        ReportFormat::Text => println!("{report}"),
+        ReportFormat::Csv => print!("{}", report.to_csv()),
+        ReportFormat::Json => println!("{}", report.to_json()),
+    }
+
+    Ok(())
+}
+
+fn render(adjacency: Adjacency, style: RenderStyle, input: &InputArg) -> Result<(), String> {
+    let style = match style {
+        RenderStyle::Ansi => Style::Ansi,
+        RenderStyle::Plain => Style::Plain,
+        RenderStyle::Auto if io::stdout().is_terminal() => Style::Ansi,
+        RenderStyle::Auto => Style::Plain,
+    };
+
+    let input = input.load(3)?;
+
+    let (symbols, values) = three::parse_with(&input, adjacency);
+    if style == Style::Plain {
+        println!("{}\n", three_render::LEGEND);
+    }
+    print!(
+        "{}",
+        three_render::render(&symbols, &values, adjacency, style)
+    );
+
+    Ok(())
+}
+
+/// Integers without decimals, other numbers rounded to two.
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn new(day: u8) -> Result<(), String> {
+    let config = config()?;
+    let changed = scaffold::scaffold(Path::new("."), &config.cache_dir, day)
+        .map_err(|err| err.to_string())?;
+
+    for path in changed {
+        println!("wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn submit(day: u8, part: Part, input: &InputArg) -> Result<(), String> {
+    let day = find_day(day)?;
+    let config = config()?;
+
+    let input = input.load(day.number)?;
+
+    let answer = day
+        .run(&input, part)
+        .map_err(|err| err.to_string())?
+        .ok_or(format!("day {} part {part} is not implemented", day.number))?;
+
+    let verdict = submit::submit(&config, day, part, &answer).map_err(|err| err.to_string())?;
+    println!("day {} part {part}: {answer} is {verdict}", day.number);
+
+    if verdict != Verdict::Correct {
+        return Err("answer was not accepted".to_string());
+    }
+
+    Ok(())
+}
+
+fn config() -> Result<Config, String> {
+    Config::load().map_err(|err| err.to_string())
+}
+
+fn find_day(day: u8) -> Result<&'static Day, String> {
+    registry::find(day).ok_or(format!("day {day} is not implemented"))
 }
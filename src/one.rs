@@ -1,22 +1,415 @@
-fn part_one(input: &str) -> i32 {
-    let mut sum = 0;
-    for line in input.lines() {
-        let digits: Vec<char> = line.chars().filter(|c| c.is_ascii_digit()).collect();
-        let first = digits.first().unwrap();
-        let last = digits.last().unwrap();
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, BufRead},
+};
 
-        let value: i32 = format!("{}{}", first, last).parse().unwrap();
-        sum += value;
+use aho_corasick::AhoCorasick;
+
+use crate::{
+    error::ParseError,
+    solver::{Answer, Solver},
+};
+
+const DAY: u8 = 1;
+
+pub struct One;
+
+impl Solver for One {
+    type Parsed = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(input.to_string())
+    }
+
+    /// Each part calibrates the input with the default policy, so a line
+    /// rejected by one part does not fail the other.
+    fn part_one(input: &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(part_one(input)?.into())
+    }
+
+    fn part_two(input: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(part_two(input).map(Answer::from))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    Ok(part_one_with(input, Policy::default())?.sum)
+}
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    Ok(part_two_with(input, Policy::default())?.sum)
+}
+
+/// Part one handling lines without a digit according to the policy.
+pub fn part_one_with(input: &str, policy: Policy) -> Result<Calibration, ParseError> {
+    scan(input.as_bytes(), policy).map_err(|err| match err {
+        ScanError::Rejected(rejected) => rejected.into(),
+        ScanError::Overflow(line) => {
+            ParseError::new(DAY, 1, "a sum fitting in 64 bits", "").on_line(line)
+        }
+        ScanError::Io(_) => unreachable!("reading a byte slice never fails"),
+    })
+}
+
+/// Part two handling lines without a digit or digit word according to the
+/// policy.
+pub fn part_two_with(input: &str, policy: Policy) -> Result<Calibration, ParseError> {
+    let vocabulary = Vocabulary::new(DIGITS.iter().chain(ENGLISH).copied())
+        .expect("the English vocabulary is valid");
+
+    Ok(calibrate(input, &vocabulary, policy)?)
+}
+
+/// What to do with a line that has no calibration value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Stop at the first rejected line.
+    #[default]
+    Abort,
+    /// Leave the line out of the sum.
+    Skip,
+    /// Count the line with a value of zero.
+    Zero,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    Empty,
+    NoDigit,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Empty => write!(f, "empty line"),
+            Reason::NoDigit => write!(f, "no digit"),
+        }
+    }
+}
+
+/// Line without a calibration value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    /// 1-based line number.
+    pub line: usize,
+    pub content: String,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            Reason::Empty => write!(f, "line {}: {}", self.line, self.reason),
+            Reason::NoDigit => write!(
+                f,
+                "line {}: {} in `{}`",
+                self.line, self.reason, self.content
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+impl Rejected {
+    fn new(line: usize, content: &str) -> Self {
+        Self {
+            line,
+            content: content.to_string(),
+            reason: if content.trim().is_empty() {
+                Reason::Empty
+            } else {
+                Reason::NoDigit
+            },
+        }
+    }
+}
+
+impl From<Rejected> for ParseError {
+    fn from(rejected: Rejected) -> Self {
+        ParseError::new(DAY, 1, "a digit", rejected.content.trim_end()).on_line(rejected.line)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: u64,
+    /// Number of lines counted in the sum, including zeroed lines.
+    pub counted: usize,
+    pub rejected: Vec<Rejected>,
+}
+
+/// Sums the calibration values of all lines, handling lines without a value
+/// according to the policy. Aborting returns the first rejected line.
+pub fn calibrate(
+    input: &str,
+    vocabulary: &Vocabulary,
+    policy: Policy,
+) -> Result<Calibration, Rejected> {
+    let mut calibration = Calibration::default();
+
+    for (idx, line) in input.lines().enumerate() {
+        let value = match vocabulary.calibration_value(line) {
+            Some(value) => value,
+            None => match calibration.reject(Rejected::new(idx + 1, line), policy)? {
+                Some(value) => value,
+                None => continue,
+            },
+        };
+
+        calibration.sum += u64::from(value);
+        calibration.counted += 1;
+    }
+
+    Ok(calibration)
+}
+
+impl Calibration {
+    /// Records a rejected line, returning the value it counts with or `None`
+    /// when it is skipped.
+    fn reject(&mut self, rejected: Rejected, policy: Policy) -> Result<Option<u32>, Rejected> {
+        match policy {
+            Policy::Abort => Err(rejected),
+            Policy::Skip => {
+                self.rejected.push(rejected);
+                Ok(None)
+            }
+            Policy::Zero => {
+                self.rejected.push(rejected);
+                Ok(Some(0))
+            }
+        }
+    }
+}
+
+/// Sums the calibration values of ASCII digits read line by line, handling
+/// lines without a digit according to the policy.
+///
+/// Lines are scanned as bytes from both ends and the line buffer is reused,
+/// so memory stays bounded by the longest line however large the input is.
+/// Only rejected lines are copied.
+pub fn scan<R: BufRead>(reader: R, policy: Policy) -> Result<Calibration, ScanError> {
+    scan_from(reader, policy, Calibration::default())
+}
+
+fn scan_from<R: BufRead>(
+    mut reader: R,
+    policy: Policy,
+    mut calibration: Calibration,
+) -> Result<Calibration, ScanError> {
+    let mut line = Vec::new();
+    let mut number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(ScanError::Io)? == 0 {
+            return Ok(calibration);
+        }
+        number += 1;
+
+        let first = line.iter().find(|b| b.is_ascii_digit());
+        let last = line.iter().rfind(|b| b.is_ascii_digit());
+        let value = match (first, last) {
+            (Some(first), Some(last)) => u64::from(first - b'0') * 10 + u64::from(last - b'0'),
+            _ => {
+                let content = String::from_utf8_lossy(&line);
+                let content = content.trim_end_matches(['\n', '\r']);
+                match calibration
+                    .reject(Rejected::new(number, content), policy)
+                    .map_err(ScanError::Rejected)?
+                {
+                    Some(value) => u64::from(value),
+                    None => continue,
+                }
+            }
+        };
+
+        calibration.sum = calibration
+            .sum
+            .checked_add(value)
+            .ok_or(ScanError::Overflow(number))?;
+        calibration.counted += 1;
+    }
+}
+
+#[derive(Debug)]
+pub enum ScanError {
+    Io(io::Error),
+    /// A line without a digit when aborting.
+    Rejected(Rejected),
+    /// The sum overflowed on the line with this 1-based number.
+    Overflow(usize),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Io(err) => write!(f, "failed to read the input, {err}"),
+            ScanError::Rejected(rejected) => write!(f, "{rejected}"),
+            ScanError::Overflow(line) => write!(f, "the sum overflows on line {line}"),
+        }
     }
+}
+
+impl std::error::Error for ScanError {}
+
+pub const DIGITS: &[(&str, u8)] = &[
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+pub const ENGLISH: &[(&str, u8)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
 
-    sum
+pub const GERMAN: &[(&str, u8)] = &[
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+pub const FRENCH: &[(&str, u8)] = &[
+    ("un", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+pub const SPANISH: &[(&str, u8)] = &[
+    ("uno", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+];
+
+/// Tokens counting as digits, e.g. [`DIGITS`] and [`ENGLISH`] combined with
+/// custom aliases, compiled into a single matcher.
+///
+/// Tokens may overlap within a line, like `eightwo`, the first digit is the
+/// token starting first and the last digit the token starting last. Tokens
+/// starting at the same position resolve to the longest one.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    matcher: AhoCorasick,
+    /// Digit of every token, indexed by pattern id.
+    digits: Vec<u8>,
 }
 
+impl Vocabulary {
+    pub fn new<'a>(
+        tokens: impl IntoIterator<Item = (&'a str, u8)>,
+    ) -> Result<Self, VocabularyError> {
+        let mut digits_of: HashMap<&str, u8> = HashMap::new();
+        for (token, digit) in tokens {
+            if token.is_empty() {
+                return Err(VocabularyError::EmptyToken);
+            }
+            if digit > 9 {
+                return Err(VocabularyError::InvalidDigit(token.to_string(), digit));
+            }
+            match digits_of.insert(token, digit) {
+                Some(previous) if previous != digit => {
+                    return Err(VocabularyError::Conflict(token.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        let (tokens, digits): (Vec<&str>, Vec<u8>) = digits_of.into_iter().unzip();
+        let matcher =
+            AhoCorasick::new(&tokens).map_err(|err| VocabularyError::Build(err.to_string()))?;
+
+        Ok(Self { matcher, digits })
+    }
+
+    /// First and last digit of the line, found in a single pass.
+    pub fn first_and_last(&self, line: &str) -> Option<(u8, u8)> {
+        let mut first: Option<aho_corasick::Match> = None;
+        let mut last: Option<aho_corasick::Match> = None;
+
+        for found in self.matcher.find_overlapping_iter(line) {
+            let key = (found.start(), std::cmp::Reverse(found.len()));
+            if first.is_none_or(|m| key < (m.start(), std::cmp::Reverse(m.len()))) {
+                first = Some(found);
+            }
+            if last.is_none_or(|m| (found.start(), found.len()) > (m.start(), m.len())) {
+                last = Some(found);
+            }
+        }
+
+        let digit = |m: aho_corasick::Match| self.digits[m.pattern().as_usize()];
+        Some((digit(first?), digit(last?)))
+    }
+
+    /// Two digit number of the first and last digit of the line.
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let (first, last) = self.first_and_last(line)?;
+
+        Some(u32::from(first) * 10 + u32::from(last))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VocabularyError {
+    EmptyToken,
+    InvalidDigit(String, u8),
+    /// The token is mapped to different digits.
+    Conflict(String),
+    Build(String),
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VocabularyError::EmptyToken => write!(f, "empty token"),
+            VocabularyError::InvalidDigit(token, digit) => {
+                write!(f, "`{token}` maps to {digit}, which is not a digit")
+            }
+            VocabularyError::Conflict(token) => write!(f, "`{token}` maps to different digits"),
+            VocabularyError::Build(err) => write!(f, "failed to build the matcher, {err}"),
+        }
+    }
+}
+
+impl std::error::Error for VocabularyError {}
+
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/one.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     #[test]
     fn test_one() {
@@ -24,14 +417,160 @@ mod test {
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
-        let res = part_one(input);
+        let res = part_one(input).unwrap();
 
         assert_eq!(res, 142)
     }
 
     #[test]
     fn solution_one() {
-        let res = part_one(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("one") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("one", Part::One));
+    }
+
+    #[test]
+    fn test_two() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+        let res = part_two(input).unwrap();
+
+        assert_eq!(res, 281)
+    }
+
+    #[test]
+    fn test_two_overlapping() {
+        assert_eq!(part_two("eightwo").unwrap(), 82);
+        assert_eq!(part_two("oneight").unwrap(), 18);
+        assert_eq!(part_two("xsevenine").unwrap(), 79);
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let vocabulary = Vocabulary::new(
+            DIGITS
+                .iter()
+                .chain(GERMAN)
+                .chain(SPANISH)
+                .copied()
+                .chain([("won", 1), ("ate", 8)]),
+        )
+        .unwrap();
+
+        assert_eq!(vocabulary.calibration_value("xfünfzig7"), Some(57));
+        assert_eq!(vocabulary.calibration_value("dosiete"), Some(27));
+        assert_eq!(vocabulary.calibration_value("wonx2ate"), Some(18));
+        assert_eq!(vocabulary.calibration_value("xyz"), None);
+
+        // The longest token wins on the same start
+        let vocabulary = Vocabulary::new([("seven", 7), ("se", 2)]).unwrap();
+        assert_eq!(vocabulary.first_and_last("seven"), Some((7, 7)));
+    }
+
+    #[test]
+    fn test_invalid_vocabulary() {
+        assert_eq!(
+            Vocabulary::new([("", 1)]).unwrap_err(),
+            VocabularyError::EmptyToken
+        );
+        assert_eq!(
+            Vocabulary::new([("ten", 10)]).unwrap_err(),
+            VocabularyError::InvalidDigit("ten".to_string(), 10)
+        );
+        assert_eq!(
+            Vocabulary::new([("six", 6), ("six", 7)]).unwrap_err(),
+            VocabularyError::Conflict("six".to_string())
+        );
+        assert!(Vocabulary::new(ENGLISH.iter().chain(FRENCH).copied()).is_ok());
+    }
+
+    #[test]
+    fn test_scan() {
+        let input = "1abc2\npqr3stu8vwx\r\na1b2c3d4e5f\ntreb7uchet";
+        assert_eq!(scan(input.as_bytes(), Policy::Abort).unwrap().sum, 142);
+
+        // Lines of a file larger than the reader's buffer
+        let input = ("x9".repeat(5_000) + "\n").repeat(3);
+        let reader = io::BufReader::with_capacity(16, input.as_bytes());
+        assert_eq!(scan(reader, Policy::Abort).unwrap().sum, 3 * 99);
+
+        let input = "12\nabc\r\n\n3";
+        assert!(matches!(
+            scan(input.as_bytes(), Policy::Abort),
+            Err(ScanError::Rejected(Rejected { line: 2, .. }))
+        ));
+        let zeroed = scan(input.as_bytes(), Policy::Zero).unwrap();
+        assert_eq!((zeroed.sum, zeroed.counted), (12 + 33, 4));
+        let vocabulary = Vocabulary::new(DIGITS.iter().copied()).unwrap();
+        assert_eq!(zeroed, calibrate(input, &vocabulary, Policy::Zero).unwrap());
+
+        assert!(matches!(
+            scan_from(
+                "1\n99\n".as_bytes(),
+                Policy::Abort,
+                Calibration {
+                    sum: u64::MAX - 20,
+                    ..Calibration::default()
+                }
+            ),
+            Err(ScanError::Overflow(2))
+        ));
+    }
+
+    #[test]
+    fn test_rejected_input() {
+        let input = "1abc2\n\nx\n";
+
+        assert_eq!(
+            part_one(input).unwrap_err().to_string(),
+            "day 1 input, line 2, column 1: expected a digit, found nothing"
+        );
+        assert_eq!(part_two_with(input, Policy::Skip).unwrap().sum, 12);
+
+        // Rejected by part one only, part two still answers
+        let parsed = One::parse("two1nine\neightwothree").unwrap();
+        assert_eq!(
+            One::part_one(&parsed).unwrap_err().to_string(),
+            "day 1 input, line 2, column 1: expected a digit, found `eightwothree`"
+        );
+        assert_eq!(One::part_two(&parsed), Some(Ok((29 + 83).into())));
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let vocabulary = Vocabulary::new(DIGITS.iter().chain(ENGLISH).copied()).unwrap();
+        let input = "two1nine\nabc\n\nxtwone3four";
+
+        let error = calibrate(input, &vocabulary, Policy::Abort).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: no digit in `abc`");
+
+        let skipped = calibrate(input, &vocabulary, Policy::Skip).unwrap();
+        assert_eq!((skipped.sum, skipped.counted), (29 + 24, 2));
+        let rejected: Vec<(usize, &Reason)> = skipped
+            .rejected
+            .iter()
+            .map(|r| (r.line, &r.reason))
+            .collect();
+        assert_eq!(rejected, [(2, &Reason::NoDigit), (3, &Reason::Empty)]);
+
+        let zeroed = calibrate(input, &vocabulary, Policy::Zero).unwrap();
+        assert_eq!((zeroed.sum, zeroed.counted), (29 + 24, 4));
+        assert_eq!(zeroed.rejected, skipped.rejected);
+    }
+
+    #[test]
+    fn solution_two() {
+        let Some(input) = cached_input("one") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("one", Part::Two));
     }
 }
@@ -0,0 +1,35 @@
+use crate::{eight, five, four, one, seven, six, solver::Day, three, two};
+
+/// All implemented days, ordered by day number.
+pub static DAYS: &[Day] = &[
+    Day::new::<one::One>(1, "one"),
+    Day::new::<two::Two>(2, "two"),
+    Day::new::<three::Three>(3, "three"),
+    Day::new::<four::Four>(4, "four"),
+    Day::new::<five::Five>(5, "five"),
+    Day::new::<six::Six>(6, "six"),
+    Day::new::<seven::Seven>(7, "seven"),
+    Day::new::<eight::Eight>(8, "eight"),
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_days_ordered() {
+        for pair in DAYS.windows(2) {
+            assert!(pair[0].number < pair[1].number);
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(7).unwrap().name, "seven");
+        assert!(find(25).is_none());
+    }
+}
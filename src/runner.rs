@@ -0,0 +1,268 @@
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    error::ParseError,
+    solver::{Answer, Day, Part},
+};
+
+/// Result of solving a single part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved(Answer),
+    NotImplemented,
+    MissingInput,
+    InvalidInput(ParseError),
+    /// The solver panicked, holds the panic message.
+    Panicked(String),
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::InvalidInput(_) | Status::Panicked(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub day: u8,
+    pub part: Part,
+    /// Time spent solving the part, parsing is not included.
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+/// Runs of all days, ordered by day and part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary(pub Vec<Run>);
+
+impl Summary {
+    pub fn is_success(&self) -> bool {
+        !self.0.iter().any(|run| run.status.is_failure())
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<[String; 5]> = self
+            .0
+            .iter()
+            .filter(|run| run.status != Status::NotImplemented)
+            .map(|run| {
+                let (answer, elapsed, status) = match &run.status {
+                    Status::Solved(answer) => (
+                        answer.to_string(),
+                        format!("{:.2?}", run.elapsed),
+                        "ok".into(),
+                    ),
+                    Status::MissingInput => ("-".into(), "-".into(), "missing input".into()),
+                    Status::InvalidInput(err) => ("-".into(), "-".into(), err.to_string()),
+                    Status::Panicked(message) => (
+                        "-".into(),
+                        format!("{:.2?}", run.elapsed),
+                        format!("panicked: {message}"),
+                    ),
+                    Status::NotImplemented => unreachable!(),
+                };
+
+                [
+                    run.day.to_string(),
+                    run.part.to_string(),
+                    answer,
+                    elapsed,
+                    status,
+                ]
+            })
+            .collect();
+
+        let header = ["day", "part", "answer", "elapsed", "status"].map(String::from);
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(&rows) {
+            writeln!(
+                f,
+                "{:>w0$}  {:>w1$}  {:<w2$}  {:>w3$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+            )?;
+        }
+
+        let solved = self
+            .0
+            .iter()
+            .filter(|run| matches!(run.status, Status::Solved(_)))
+            .count();
+        let failed = self.0.iter().filter(|run| run.status.is_failure()).count();
+        write!(f, "{solved} solved, {failed} failed")
+    }
+}
+
+/// Solves every part of `days` with the input returned by `load_input`.
+///
+/// With `parallel` the days and their parts run on the rayon thread pool,
+/// otherwise one after another, which gives more accurate timings. A panic
+/// only fails the part it happened in.
+pub fn run_all<F>(days: &[Day], load_input: F, parallel: bool) -> Summary
+where
+    F: Fn(&Day) -> Option<String> + Sync,
+{
+    let run_day = |day: &Day| -> Vec<Run> {
+        let failed = |status: Status| -> Vec<Run> {
+            Part::ALL
+                .map(|part| Run {
+                    day: day.number,
+                    part,
+                    elapsed: Duration::ZERO,
+                    status: status.clone(),
+                })
+                .to_vec()
+        };
+
+        let Some(input) = load_input(day) else {
+            return failed(Status::MissingInput);
+        };
+        let parsed = match catch_panic(|| day.parse(&input)) {
+            Ok(Ok(parsed)) => parsed,
+            Ok(Err(err)) => return failed(Status::InvalidInput(err)),
+            Err(message) => return failed(Status::Panicked(message)),
+        };
+
+        let run_part = |part: Part| -> Run {
+            let start = Instant::now();
+            let status = match catch_panic(|| day.solve(&parsed, part)) {
+                Ok(Some(Ok(answer))) => Status::Solved(answer),
+                Ok(Some(Err(err))) => Status::InvalidInput(err),
+                Ok(None) => Status::NotImplemented,
+                Err(message) => Status::Panicked(message),
+            };
+
+            Run {
+                day: day.number,
+                part,
+                elapsed: start.elapsed(),
+                status,
+            }
+        };
+
+        if parallel {
+            Part::ALL.into_par_iter().map(run_part).collect()
+        } else {
+            Part::ALL.into_iter().map(run_part).collect()
+        }
+    };
+
+    let runs = if parallel {
+        days.par_iter().flat_map_iter(run_day).collect()
+    } else {
+        days.iter().flat_map(run_day).collect()
+    };
+
+    Summary(runs)
+}
+
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{registry::DAYS, solver::Solver};
+
+    fn sample_input(day: &Day) -> Option<String> {
+        match day.name {
+            "six" => Some("Time: 7 15 30\nDistance: 9 40 200".to_string()),
+            "eight" => Some("R\n\nAAA = (AAA, ZZZ)".to_string()),
+            "four" => Some("Card 1: 1 2 | x".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Day whose part one panics.
+    struct Panics;
+
+    impl Solver for Panics {
+        type Parsed = ();
+
+        fn parse(_input: &str) -> Result<Self::Parsed, ParseError> {
+            Ok(())
+        }
+
+        fn part_one(_parsed: &Self::Parsed) -> Result<Answer, ParseError> {
+            panic!("no answer")
+        }
+
+        fn part_two(_parsed: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+            Some(Ok(2.into()))
+        }
+    }
+
+    #[test]
+    fn test_run_all() {
+        for parallel in [true, false] {
+            let summary = run_all(DAYS, sample_input, parallel);
+            let status = |day: u8, part: Part| {
+                let run = summary.0.iter().find(|r| r.day == day && r.part == part);
+                &run.unwrap().status
+            };
+
+            assert_eq!(status(6, Part::One), &Status::Solved(288.into()));
+            assert_eq!(status(6, Part::Two), &Status::Solved(71503.into()));
+            assert!(matches!(status(8, Part::One), Status::InvalidInput(_)));
+            assert!(matches!(status(4, Part::Two), Status::InvalidInput(_)));
+            assert_eq!(status(1, Part::One), &Status::MissingInput);
+            assert_eq!(status(1, Part::Two), &Status::MissingInput);
+
+            assert!(!summary.is_success());
+            let days: Vec<u8> = summary.0.iter().map(|r| r.day).collect();
+            assert!(days.is_sorted());
+        }
+    }
+
+    #[test]
+    fn test_run_panic() {
+        static PANICS: &[Day] = &[Day::new::<Panics>(25, "panics")];
+
+        for parallel in [true, false] {
+            let summary = run_all(PANICS, |_| Some(String::new()), parallel);
+            let statuses: Vec<&Status> = summary.0.iter().map(|r| &r.status).collect();
+
+            assert_eq!(
+                statuses,
+                [
+                    &Status::Panicked("no answer".to_string()),
+                    &Status::Solved(2.into())
+                ]
+            );
+            assert!(!summary.is_success());
+        }
+    }
+}
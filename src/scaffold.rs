@@ -0,0 +1,448 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Module of a new day, `$DAY`, `$NAME` and `$TYPE` are replaced.
+const TEMPLATE: &str = r#"use std::str::FromStr;
+
+use crate::{
+    error::{parse_lines, ParseError},
+    solver::{Answer, Solver},
+};
+
+const DAY: u8 = $DAY;
+
+pub struct $TYPE;
+
+impl Solver for $TYPE {
+    type Parsed = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(lines: &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(lines).into())
+    }
+
+    fn part_two(lines: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(lines).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_one(&parse_input(input)?))
+}
+
+pub fn part_two(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_two(&parse_input(input)?))
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Line>, ParseError> {
+    parse_lines(input)
+}
+
+// TODO: solve the parts, the day answers 0 until then
+fn solve_one(_lines: &[Line]) -> i32 {
+    0
+}
+
+fn solve_two(_lines: &[Line]) -> i32 {
+    0
+}
+
+#[derive(Debug)]
+pub struct Line(pub String);
+
+impl FromStr for Line {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            return Err(ParseError::new(DAY, 1, "a line", value));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
+
+    const SAMPLE_INPUT: &str = "";
+
+    #[test]
+    fn test_one() {
+        assert_eq!(part_one(SAMPLE_INPUT).unwrap(), 0)
+    }
+
+    #[test]
+    fn solution_one() {
+        let Some(input) = cached_input("$NAME") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("$NAME", Part::One));
+    }
+
+    #[test]
+    fn test_two() {
+        assert_eq!(part_two(SAMPLE_INPUT).unwrap(), 0)
+    }
+
+    #[test]
+    fn solution_two() {
+        let Some(input) = cached_input("$NAME") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("$NAME", Part::Two));
+    }
+}
+"#;
+
+const NAMES: [&str; 25] = [
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+    "twenty_one",
+    "twenty_two",
+    "twenty_three",
+    "twenty_four",
+    "twenty_five",
+];
+
+const MAX_WIDTH: usize = 100;
+
+/// Module name of the day, `None` outside of 1-25.
+pub fn day_name(day: u8) -> Option<&'static str> {
+    NAMES.get(usize::from(day).checked_sub(1)?).copied()
+}
+
+/// Name of the day's [`crate::solver::Solver`] type, e.g. `TwentyOne`.
+fn type_name(name: &str) -> String {
+    name.split('_')
+        .map(|word| word[..1].to_ascii_uppercase() + &word[1..])
+        .collect()
+}
+
+/// Creates the module of a new day in `root/src`, registers it in `lib.rs`
+/// and the registry and creates an empty input in `cache_dir` unless one is
+/// cached already. Returns the created or modified files.
+pub fn scaffold(root: &Path, cache_dir: &Path, day: u8) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let name = day_name(day).ok_or(ScaffoldError::InvalidDay(day))?;
+    let type_name = type_name(name);
+
+    let module_path = root.join("src").join(format!("{name}.rs"));
+    let lib_path = root.join("src").join("lib.rs");
+    let registry_path = root.join("src").join("registry.rs");
+
+    if module_path.exists() {
+        return Err(ScaffoldError::Exists(module_path));
+    }
+
+    // Prepare every change first so a failure leaves the tree untouched
+    let lib = register_module(&read(&lib_path)?, name)
+        .ok_or_else(|| ScaffoldError::Registration(lib_path.clone()))?;
+    let registry = register_day(&read(&registry_path)?, day, name, &type_name)
+        .ok_or_else(|| ScaffoldError::Registration(registry_path.clone()))?;
+    let module = TEMPLATE
+        .replace("$DAY", &day.to_string())
+        .replace("$NAME", name)
+        .replace("$TYPE", &type_name);
+
+    write(&module_path, &module)?;
+    write(&lib_path, &lib)?;
+    write(&registry_path, &registry)?;
+    let mut changed = vec![module_path, lib_path, registry_path];
+
+    let input_path = cache_dir.join(format!("{name}.txt"));
+    if !input_path.exists() {
+        fs::create_dir_all(cache_dir).map_err(|err| ScaffoldError::Io(cache_dir.into(), err))?;
+        write(&input_path, "")?;
+        changed.push(input_path);
+    }
+
+    Ok(changed)
+}
+
+/// Adds `pub mod name;` to the alphabetically ordered modules of `lib.rs`.
+fn register_module(lib: &str, name: &str) -> Option<String> {
+    let declaration = format!("pub mod {name};");
+    let mut lines: Vec<&str> = lib.lines().collect();
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+
+    let module_of = |line: &str| -> Option<String> {
+        let line = line.strip_prefix("pub ").unwrap_or(line);
+        Some(line.strip_prefix("mod ")?.strip_suffix(';')?.to_string())
+    };
+
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|&idx| module_of(lines[idx]).is_some())
+        .collect();
+    let mut idx = match modules
+        .iter()
+        .find(|&&idx| module_of(lines[idx]).unwrap().as_str() > name)
+    {
+        Some(&idx) => idx,
+        None => modules.last()? + 1,
+    };
+    // Keep attributes with the module they belong to
+    while idx > 0 && lines[idx - 1].starts_with("#[") {
+        idx -= 1;
+    }
+
+    lines.insert(idx, &declaration);
+    Some(lines.join("\n") + "\n")
+}
+
+/// Imports the module in the registry and adds the day to `DAYS`.
+fn register_day(registry: &str, day: u8, name: &str, type_name: &str) -> Option<String> {
+    let start = registry.find("use crate::{")?;
+    let end = start + registry[start..].find("};")? + "};".len();
+
+    let mut imports: Vec<&str> = registry[start + "use crate::{".len()..end - "};".len()]
+        .split(',')
+        .map(str::trim)
+        .filter(|import| !import.is_empty())
+        .collect();
+    imports.push(name);
+    imports.sort();
+    imports.dedup();
+
+    let entry = format!("    Day::new::<{name}::{type_name}>({day}, \"{name}\"),");
+    let mut lines: Vec<String> = registry[end..].lines().map(String::from).collect();
+
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let (_, number) = line
+                .trim_start()
+                .strip_prefix("Day::new::<")?
+                .split_once(">(")?;
+            Some((idx, number.split(',').next()?.parse().ok()?))
+        })
+        .collect();
+    if days.iter().any(|&(_, number)| number == day) {
+        return None;
+    }
+    let idx = match days.iter().find(|&&(_, number)| number > day) {
+        Some(&(idx, _)) => idx,
+        None => days.last()?.0 + 1,
+    };
+    lines.insert(idx, entry);
+
+    Some(format!(
+        "{}{}{}\n",
+        &registry[..start],
+        format_imports(&imports),
+        lines.join("\n")
+    ))
+}
+
+/// Formats a `use crate::{...};` the way rustfmt does.
+fn format_imports(imports: &[&str]) -> String {
+    let single = format!("use crate::{{{}}};", imports.join(", "));
+    if single.len() <= MAX_WIDTH {
+        return single;
+    }
+
+    let mut formatted = String::from("use crate::{\n");
+    let mut line = String::new();
+    for import in imports {
+        if !line.is_empty() && 4 + line.len() + 1 + import.len() + 1 > MAX_WIDTH {
+            formatted.push_str(&format!("    {line}\n"));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(import);
+        line.push(',');
+    }
+    formatted.push_str(&format!("    {line}\n}};"));
+
+    formatted
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|err| ScaffoldError::Io(path.into(), err))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, contents).map_err(|err| ScaffoldError::Io(path.into(), err))
+}
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    InvalidDay(u8),
+    /// The module of the day exists already.
+    Exists(PathBuf),
+    /// The file has an unexpected layout or registers the day already.
+    Registration(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => write!(f, "day {day} is not between 1 and 25"),
+            ScaffoldError::Exists(path) => write!(f, "{} exists already", path.display()),
+            ScaffoldError::Registration(path) => {
+                write!(f, "failed to register the day in {}", path.display())
+            }
+            ScaffoldError::Io(path, err) => write!(f, "{}: {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Copy of the crate sources in a temporary directory.
+    fn sources() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        let src = root.path().join("src");
+        fs::create_dir(&src).unwrap();
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for file in ["lib.rs", "registry.rs"] {
+            fs::copy(manifest_dir.join("src").join(file), src.join(file)).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(day_name(1), Some("one"));
+        assert_eq!(day_name(25), Some("twenty_five"));
+        assert_eq!(day_name(0), None);
+        assert_eq!(day_name(26), None);
+        assert_eq!(type_name("twenty_one"), "TwentyOne");
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = sources();
+        let cache_dir = root.path().join("assets");
+
+        let changed = scaffold(root.path(), &cache_dir, 21).unwrap();
+        assert_eq!(changed.len(), 4);
+
+        let module = fs::read_to_string(root.path().join("src/twenty_one.rs")).unwrap();
+        assert!(module.contains("const DAY: u8 = 21;"));
+        assert!(module.contains("impl Solver for TwentyOne {"));
+        assert!(module.contains("cached_input(\"twenty_one\")"));
+        // The registered day runs before it is solved
+        assert!(module
+            .contains("fn part_two(lines: &Self::Parsed) -> Option<Result<Answer, ParseError>>"));
+        assert!(!module.contains("todo!"));
+        assert!(!module.contains("dbg!"));
+        assert!(module.contains(r#"expected_answer("twenty_one", Part::Two)"#));
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("twenty_one.txt")).unwrap(),
+            ""
+        );
+
+        let lib = fs::read_to_string(root.path().join("src/lib.rs")).unwrap();
+        let modules: Vec<&str> = lib.lines().filter(|l| l.starts_with("pub mod")).collect();
+        assert!(modules.is_sorted());
+        assert!(modules.contains(&"pub mod twenty_one;"));
+
+        let registry = fs::read_to_string(root.path().join("src/registry.rs")).unwrap();
+        assert!(registry.contains("twenty_one"));
+        assert!(registry.contains("    Day::new::<twenty_one::TwentyOne>(21, \"twenty_one\"),\n];"));
+    }
+
+    #[test]
+    fn test_scaffold_existing() {
+        let root = sources();
+        let cache_dir = root.path().join("assets");
+        fs::create_dir(&cache_dir).unwrap();
+        fs::write(cache_dir.join("twelve.txt"), "cached").unwrap();
+
+        scaffold(root.path(), &cache_dir, 12).unwrap();
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("twelve.txt")).unwrap(),
+            "cached"
+        );
+
+        let lib = fs::read_to_string(root.path().join("src/lib.rs")).unwrap();
+        assert!(matches!(
+            scaffold(root.path(), &cache_dir, 12),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(root.path().join("src/lib.rs")).unwrap(),
+            lib
+        );
+
+        // Registered days are refused even without a module file
+        assert!(matches!(
+            scaffold(root.path(), &cache_dir, 2),
+            Err(ScaffoldError::Registration(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_imports() {
+        let imports = ["eight", "five", "solver::Day", "three"];
+        assert_eq!(
+            format_imports(&imports),
+            "use crate::{eight, five, solver::Day, three};"
+        );
+
+        let imports = [
+            "eight",
+            "eighteen",
+            "eleven",
+            "fifteen",
+            "five",
+            "four",
+            "fourteen",
+            "nine",
+            "nineteen",
+            "one",
+            "seven",
+            "seventeen",
+            "six",
+            "sixteen",
+            "solver::Day",
+            "ten",
+        ];
+        assert_eq!(
+            format_imports(&imports),
+            "use crate::{
+    eight, eighteen, eleven, fifteen, five, four, fourteen, nine, nineteen, one, seven, seventeen,
+    six, sixteen, solver::Day, ten,
+};"
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{
+    error::ParseError,
+    seven_one, seven_two,
+    solver::{Answer, Solver},
+};
+
+/// Day seven keeps each part in its own module because the joker rule of
+/// part two changes how the cards are parsed and ordered.
+pub struct Seven;
+
+impl Solver for Seven {
+    type Parsed = (Vec<seven_one::Hand>, Vec<seven_two::Hand>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok((
+            seven_one::parse_input(input)?,
+            seven_two::parse_input(input)?,
+        ))
+    }
+
+    fn part_one((hands, _): &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(seven_one::total_winnings(hands).into())
+    }
+
+    fn part_two((_, hands): &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(seven_two::total_winnings(hands).into()))
+    }
+}
@@ -1,7 +1,15 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, str::FromStr};
 
-fn solution(input: &str) -> u32 {
-    let mut hands = parse_input(input);
+use crate::error::{column_of, end_of, parse_lines, ParseError};
+
+const DAY: u8 = 7;
+
+pub fn solution(input: &str) -> Result<u32, ParseError> {
+    Ok(total_winnings(&parse_input(input)?))
+}
+
+pub fn total_winnings(hands: &[Hand]) -> u32 {
+    let mut hands: Vec<&Hand> = hands.iter().collect();
     hands.sort();
 
     hands
@@ -11,82 +19,91 @@ fn solution(input: &str) -> u32 {
         .sum()
 }
 
-fn parse_input(input: &str) -> Vec<Hand> {
-    input.lines().map(Hand::from).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Hand>, ParseError> {
+    parse_lines(input)
 }
 
 #[derive(Debug)]
-struct Hand {
-    cards: Vec<Card>,
-    hand_type: HandType,
-    bid: u32,
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub hand_type: HandType,
+    pub bid: u32,
 }
 
+/// Hands are equal when they rank the same, bids are not compared.
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
-        if self.hand_type != other.hand_type {
-            return false;
-        }
-
-        let count = self
-            .cards
-            .iter()
-            .zip(other.cards.iter())
-            .filter(|(a, b)| a != b)
-            .count();
-
-        count > 0
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
         match self.hand_type.partial_cmp(&other.hand_type).unwrap() {
-            std::cmp::Ordering::Less => Some(Ordering::Less),
-            std::cmp::Ordering::Greater => Some(Ordering::Greater),
-            std::cmp::Ordering::Equal => {
+            Ordering::Less => Ordering::Less,
+            Ordering::Greater => Ordering::Greater,
+            Ordering::Equal => {
                 // Compare cards
                 for (self_card, other_card) in self.cards.iter().zip(other.cards.iter()) {
-                    match self_card.partial_cmp(other_card).unwrap() {
-                        Ordering::Less => return Some(Ordering::Less),
-                        Ordering::Greater => return Some(Ordering::Greater),
+                    match self_card.cmp(other_card) {
+                        Ordering::Less => return Ordering::Less,
+                        Ordering::Greater => return Ordering::Greater,
                         Ordering::Equal => continue,
                     }
                 }
 
-                panic!()
+                Ordering::Equal
             }
         }
     }
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
 impl Eq for Hand {}
 
-impl From<&str> for Hand {
-    fn from(value: &str) -> Self {
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         let mut split = value.split_ascii_whitespace();
 
-        let cards: Vec<Card> = split.next().unwrap().chars().map(Card::from).collect();
-        let bid: u32 = split.next().unwrap().parse().unwrap();
+        let cards = split
+            .next()
+            .ok_or_else(|| ParseError::at(DAY, value, value, "five cards"))?;
+        let column = column_of(value, cards);
+        if cards.chars().count() != 5 {
+            return Err(ParseError::at(DAY, value, cards, "five cards"));
+        }
+        let cards = cards
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| Card::try_from(c).map_err(|e| e.shift_column(column + idx)))
+            .collect::<Result<Vec<Card>, _>>()?;
+
+        let bid = split
+            .next()
+            .ok_or_else(|| ParseError::at(DAY, value, end_of(value), "a bid"))?;
+        let bid: u32 = bid
+            .parse()
+            .map_err(|_| ParseError::at(DAY, value, bid, "a bid"))?;
         let hand_type = HandType::from(cards.as_ref());
 
-        Self {
+        Ok(Self {
             cards,
             hand_type,
             bid,
-        }
+        })
     }
 }
 
 /// The cards are ordered from the lowest to the highest.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Card {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Card {
     Joker,
     Two,
     Three,
@@ -102,9 +119,11 @@ enum Card {
     A,
 }
 
-impl From<char> for Card {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for Card {
+    type Error = ParseError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        let card = match value {
             '2' => Self::Two,
             '3' => Self::Three,
             '4' => Self::Four,
@@ -118,13 +137,18 @@ impl From<char> for Card {
             'Q' => Self::Q,
             'K' => Self::K,
             'A' => Self::A,
-            _ => panic!("unexpected value"),
-        }
+            _ => {
+                let expected = "a card of `23456789TJQKA`";
+                return Err(ParseError::new(DAY, 1, expected, &value.to_string()));
+            }
+        };
+
+        Ok(card)
     }
 }
 
 #[derive(Debug, PartialEq, PartialOrd)]
-enum HandType {
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -162,7 +186,7 @@ impl HandType {
         // Get unique cards and their counts
         let mut map = HashMap::new();
         for card in cards {
-            let entry = map.entry(card.clone()).or_insert(0_u8);
+            let entry = map.entry(*card).or_insert(0_u8);
             *entry += 1;
         }
 
@@ -217,8 +241,7 @@ impl From<&[Card]> for HandType {
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/seven.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     const SAMPLE_INPUT: &str = "32T3K 765
 T55J5 684
@@ -228,13 +251,27 @@ QQQJA 483";
 
     #[test]
     fn test_sample() {
-        let res = solution(SAMPLE_INPUT);
+        let res = solution(SAMPLE_INPUT).unwrap();
         assert_eq!(res, 5905)
     }
 
     #[test]
     fn test_solution() {
-        let res = solution(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("seven") else {
+            return;
+        };
+        let res = solution(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("seven", Part::Two));
+    }
+
+    #[test]
+    fn test_identical_hands() {
+        // Identical hands keep their input order
+        let res = solution("T55J5 4\nT55J5 7\n32T3K 1").unwrap();
+        assert_eq!(res, 1 + 4 * 2 + 7 * 3);
+
+        let hand = |value: &str| value.parse::<Hand>().unwrap();
+        assert!(hand("T55J5 4") == hand("T55J5 7"));
+        assert!(hand("T55J5 4") != hand("T55J4 4"));
     }
 }
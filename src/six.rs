@@ -1,6 +1,42 @@
-fn part_one(input: &str) -> u64 {
-    let races = parse_input_one(input);
+use crate::{
+    error::{end_of, ParseError},
+    solver::{Answer, Solver},
+};
 
+const DAY: u8 = 6;
+
+pub struct Six;
+
+impl Solver for Six {
+    /// Part two reads the same lines as a single race with the spaces removed.
+    type Parsed = (Vec<Race>, Race);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok((parse_input_one(input)?, parse_input_two(input)?))
+    }
+
+    fn part_one((races, _): &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(races).into())
+    }
+
+    fn part_two((_, race): &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(race.wins_count().into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let races = parse_input_one(input)?;
+
+    Ok(solve_one(&races))
+}
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let race = parse_input_two(input)?;
+
+    Ok(race.wins_count())
+}
+
+fn solve_one(races: &[Race]) -> u64 {
     races
         .iter()
         .map(|r| r.wins_count())
@@ -8,35 +44,17 @@ fn part_one(input: &str) -> u64 {
         .unwrap()
 }
 
-fn part_two(input: &str) -> u64 {
-    let race = parse_input_two(input);
-    let race = dbg!(race);
-
-    race.wins_count()
-}
+pub fn parse_input_one(input: &str) -> Result<Vec<Race>, ParseError> {
+    let (line, times) = parse_line(input, 1, "Time")?;
+    let times = parse_numbers(line, times).map_err(|e| e.on_line(1))?;
 
-fn parse_input_one(input: &str) -> Vec<Race> {
-    let mut lines = input.lines();
+    let (line, distances) = parse_line(input, 2, "Distance")?;
+    let distances = parse_numbers(line, distances).map_err(|e| e.on_line(2))?;
 
-    let times: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split(':')
-        .last()
-        .unwrap()
-        .split_ascii_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    let distances: Vec<u64> = lines
-        .next()
-        .unwrap()
-        .split(':')
-        .last()
-        .unwrap()
-        .split_ascii_whitespace()
-        .map(|s| s.parse().unwrap())
-        .collect();
+    if times.len() != distances.len() {
+        let expected = format!("{} distances", times.len());
+        return Err(ParseError::at(DAY, line, end_of(line), expected).on_line(2));
+    }
 
     let mut races = vec![];
     for i in 0..times.len() {
@@ -46,50 +64,69 @@ fn parse_input_one(input: &str) -> Vec<Race> {
         races.push(Race { time, distance })
     }
 
-    races
+    Ok(races)
 }
 
-fn parse_input_two(input: &str) -> Race {
-    let mut lines = input.lines();
+pub fn parse_input_two(input: &str) -> Result<Race, ParseError> {
+    let (line, time) = parse_line(input, 1, "Time")?;
+    let time = parse_kerned_number(line, time).map_err(|e| e.on_line(1))?;
 
-    let time = lines
-        .next()
-        .unwrap()
-        .split(':')
-        .last()
-        .unwrap()
-        .replace(' ', "")
-        .parse()
-        .unwrap();
+    let (line, distance) = parse_line(input, 2, "Distance")?;
+    let distance = parse_kerned_number(line, distance).map_err(|e| e.on_line(2))?;
 
-    let distance = lines
-        .next()
-        .unwrap()
-        .split(':')
-        .last()
-        .unwrap()
+    Ok(Race { time, distance })
+}
+
+/// Returns the 1-based `line_number` of the input and the numbers after its
+/// `label:`.
+fn parse_line<'a>(
+    input: &'a str,
+    line_number: usize,
+    label: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    let line = input.lines().nth(line_number - 1).unwrap_or_default();
+
+    let (_, numbers) = line.split_once(':').ok_or_else(|| {
+        let expected = format!("`{label}:` followed by numbers");
+        ParseError::at(DAY, line, end_of(line), expected).on_line(line_number)
+    })?;
+
+    Ok((line, numbers))
+}
+
+fn parse_numbers(line: &str, numbers: &str) -> Result<Vec<u64>, ParseError> {
+    numbers
+        .split_ascii_whitespace()
+        .map(|s| {
+            s.parse()
+                .map_err(|_| ParseError::at(DAY, line, s, "a number"))
+        })
+        .collect()
+}
+
+/// Single number written with spaces between its digits.
+fn parse_kerned_number(line: &str, numbers: &str) -> Result<u64, ParseError> {
+    numbers
         .replace(' ', "")
         .parse()
-        .unwrap();
-
-    Race { time, distance }
+        .map_err(|_| ParseError::at(DAY, line, numbers.trim(), "a number"))
 }
 
 #[derive(Debug)]
-struct Race {
-    time: u64,
-    distance: u64,
+pub struct Race {
+    pub time: u64,
+    pub distance: u64,
 }
 
 impl Race {
-    fn wins_count(&self) -> u64 {
+    pub fn wins_count(&self) -> u64 {
         (0..self.time)
             .map(|hold| self.race_distance(hold))
             .filter(|d| *d > self.distance)
             .count() as u64
     }
 
-    fn race_distance(&self, hold_duration: u64) -> u64 {
+    pub fn race_distance(&self, hold_duration: u64) -> u64 {
         let speed = hold_duration;
         let remaining_time = self.time - hold_duration;
         speed * remaining_time
@@ -99,32 +136,48 @@ impl Race {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
-    const INPUT: &str = include_str!("../assets/six.txt");
     const SAMPLE_INPUT: &str = "Time:      7  15   30
 Distance:  9  40  200";
 
     #[test]
     fn test_one() {
-        let res = part_one(SAMPLE_INPUT);
+        let res = part_one(SAMPLE_INPUT).unwrap();
         assert_eq!(res, 288);
     }
 
     #[test]
     fn solution_one() {
-        let res = part_one(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("six") else {
+            return;
+        };
+        let res = part_one(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("six", Part::One));
     }
 
     #[test]
     fn test_two() {
-        let res = part_two(SAMPLE_INPUT);
+        let res = part_two(SAMPLE_INPUT).unwrap();
         assert_eq!(res, 71503);
     }
 
     #[test]
     fn solution_two() {
-        let res = part_two(INPUT);
-        dbg!(res);
+        let Some(input) = cached_input("six") else {
+            return;
+        };
+        let res = part_two(&input).unwrap();
+        assert_eq!(res.to_string(), expected_answer("six", Part::Two));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = parse_input_one("Time:      7  15   30\nDistance:  9  40").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 17));
+        assert_eq!(error.expected, "3 distances");
+
+        let error = parse_input_two("Time:      7  1x   30\nDistance:  9  40").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 12));
     }
 }
@@ -0,0 +1,149 @@
+use std::{any::Any, fmt, str::FromStr};
+
+use crate::error::ParseError;
+
+/// Common interface implemented by every day.
+///
+/// The input is parsed once and both parts are solved from the parsed model.
+pub trait Solver {
+    type Parsed: Any + Send + Sync;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+
+    /// Fails when the input parses but lacks something only this part needs.
+    fn part_one(parsed: &Self::Parsed) -> Result<Answer, ParseError>;
+
+    /// Days without a second part keep the default.
+    fn part_two(_parsed: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        None
+    }
+}
+
+/// Answer of a single part, kept in the textual form it is submitted in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Answer(String);
+
+impl Answer {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(value: $ty) -> Self {
+                    Self(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(i32, i64, u32, u64, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" | "one" => Ok(Part::One),
+            "2" | "two" => Ok(Part::Two),
+            _ => Err(format!("unknown part `{s}`, expected 1 or 2")),
+        }
+    }
+}
+
+/// Parsed input of a day with its concrete type erased.
+pub struct Parsed(Box<dyn Any + Send + Sync>);
+
+/// Registry entry of a day, see [`crate::registry::DAYS`].
+pub struct Day {
+    pub number: u8,
+    /// Name of the module and of the input asset.
+    pub name: &'static str,
+    parse: fn(&str) -> Result<Parsed, ParseError>,
+    solve: fn(&Parsed, Part) -> Option<Result<Answer, ParseError>>,
+}
+
+impl Day {
+    pub const fn new<S: Solver>(number: u8, name: &'static str) -> Self {
+        Self {
+            number,
+            name,
+            parse: parse_erased::<S>,
+            solve: solve_erased::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// Returns `None` when the part is not implemented.
+    pub fn solve(&self, parsed: &Parsed, part: Part) -> Option<Result<Answer, ParseError>> {
+        (self.solve)(parsed, part)
+    }
+
+    pub fn run(&self, input: &str, part: Part) -> Result<Option<Answer>, ParseError> {
+        self.solve(&self.parse(input)?, part).transpose()
+    }
+}
+
+fn parse_erased<S: Solver>(input: &str) -> Result<Parsed, ParseError> {
+    Ok(Parsed(Box::new(S::parse(input)?)))
+}
+
+fn solve_erased<S: Solver>(parsed: &Parsed, part: Part) -> Option<Result<Answer, ParseError>> {
+    let parsed = parsed
+        .0
+        .downcast_ref::<S::Parsed>()
+        .expect("parsed input belongs to a different day");
+
+    match part {
+        Part::One => Some(S::part_one(parsed)),
+        Part::Two => S::part_two(parsed),
+    }
+}
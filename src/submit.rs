@@ -0,0 +1,418 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{Config, InputError},
+    solver::{Answer, Day, Part},
+};
+
+/// File in the input cache with every submitted answer.
+pub const HISTORY_FILE: &str = "submissions.json";
+
+/// Cooldown after a wrong answer when the response does not state one.
+const DEFAULT_COOLDOWN: u64 = 60;
+
+/// Outcome of a submitted answer as reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted during the cooldown, holds the seconds left to wait.
+    Wait(u64),
+    /// The part is solved already or its previous part is not.
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Incorrect | Verdict::TooHigh | Verdict::TooLow
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("correct"),
+            Verdict::Incorrect => f.write_str("incorrect"),
+            Verdict::TooHigh => f.write_str("incorrect, too high"),
+            Verdict::TooLow => f.write_str("incorrect, too low"),
+            Verdict::Wait(seconds) => write!(f, "submitted too recently, wait {seconds}s"),
+            Verdict::WrongLevel => f.write_str("part already solved or not unlocked yet"),
+            Verdict::Unknown => f.write_str("unknown response"),
+        }
+    }
+}
+
+/// Verdict and cooldown in seconds parsed from the response page.
+pub fn parse_response(html: &str) -> (Verdict, u64) {
+    let text = html.to_ascii_lowercase();
+    let cooldown = parse_cooldown(&text);
+
+    let verdict = if text.contains("that's the right answer") {
+        Verdict::Correct
+    } else if text.contains("answer too recently") {
+        Verdict::Wait(cooldown.unwrap_or(DEFAULT_COOLDOWN))
+    } else if text.contains("not the right answer") {
+        if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Incorrect
+        }
+    } else if text.contains("solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+
+    let cooldown = match verdict {
+        Verdict::Wait(seconds) => seconds,
+        v if v.is_wrong() => cooldown.unwrap_or(DEFAULT_COOLDOWN),
+        _ => 0,
+    };
+
+    (verdict, cooldown)
+}
+
+/// Parses "you have 1m 23s left to wait" and "please wait 5 minutes".
+fn parse_cooldown(text: &str) -> Option<u64> {
+    if let Some(end) = text.find(" left to wait") {
+        let start = text[..end].rfind("you have ")?;
+
+        return text[start + "you have ".len()..end]
+            .split_whitespace()
+            .map(|part| {
+                let (unit_start, _) = part.char_indices().next_back()?;
+                let (value, unit) = part.split_at(unit_start);
+                let value: u64 = value.parse().ok()?;
+                match unit {
+                    "h" => Some(value * 3600),
+                    "m" => Some(value * 60),
+                    "s" => Some(value),
+                    _ => None,
+                }
+            })
+            .sum();
+    }
+
+    let start = text.find("please wait ")?;
+    let mut words = text[start + "please wait ".len()..].split_whitespace();
+    let value = match words.next()? {
+        "one" => 1,
+        value => value.parse().ok()?,
+    };
+
+    match words.next()? {
+        unit if unit.starts_with("minute") => Some(value * 60),
+        unit if unit.starts_with("second") => Some(value),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Unix time of the submission.
+    pub submitted_at: u64,
+    /// Unix time before which no other answer may be submitted.
+    pub wait_until: u64,
+}
+
+/// Reason to not submit an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    Solved(String),
+    KnownWrong(Verdict),
+    /// An answer at least this high was too high.
+    TooHigh(i64),
+    /// An answer at least this low was too low.
+    TooLow(i64),
+    /// Seconds left of the cooldown.
+    Cooldown(u64),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved(answer) => write!(f, "already solved with {answer}"),
+            Refusal::KnownWrong(verdict) => write!(f, "answer was already submitted, {verdict}"),
+            Refusal::TooHigh(bound) => write!(f, "{bound} was already too high"),
+            Refusal::TooLow(bound) => write!(f, "{bound} was already too low"),
+            Refusal::Cooldown(seconds) => write!(f, "cooldown, wait {seconds}s"),
+        }
+    }
+}
+
+/// Local record of submitted answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History(pub Vec<Attempt>);
+
+impl History {
+    pub fn load(path: &Path) -> Result<Self, SubmitError> {
+        match fs::read_to_string(path) {
+            Ok(history) => serde_json::from_str(&history)
+                .map_err(|err| SubmitError::History(path.into(), err.to_string())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(InputError::Io(path.into(), err).into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SubmitError> {
+        let history = serde_json::to_string_pretty(self).expect("history is always serializable");
+
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, history).map_err(|err| InputError::Io(partial.clone(), err))?;
+        fs::rename(&partial, path).map_err(|err| InputError::Io(path.into(), err))?;
+
+        Ok(())
+    }
+
+    /// Checks whether the answer may be submitted at the Unix time `now`.
+    pub fn check(&self, day: u8, part: Part, answer: &Answer, now: u64) -> Result<(), Refusal> {
+        let value: Option<i64> = answer.as_str().parse().ok();
+
+        for attempt in self.attempts(day, part) {
+            if attempt.verdict == Verdict::Correct {
+                return Err(Refusal::Solved(attempt.answer.clone()));
+            }
+            if attempt.answer == answer.as_str() && attempt.verdict.is_wrong() {
+                return Err(Refusal::KnownWrong(attempt.verdict));
+            }
+
+            let (Some(value), Ok(previous)) = (value, attempt.answer.parse::<i64>()) else {
+                continue;
+            };
+            match attempt.verdict {
+                Verdict::TooHigh if value >= previous => return Err(Refusal::TooHigh(previous)),
+                Verdict::TooLow if value <= previous => return Err(Refusal::TooLow(previous)),
+                _ => {}
+            }
+        }
+
+        // The cooldown applies to all puzzles
+        let wait_until = self.0.iter().map(|a| a.wait_until).max().unwrap_or(0);
+        if wait_until > now {
+            return Err(Refusal::Cooldown(wait_until - now));
+        }
+
+        Ok(())
+    }
+
+    fn attempts(&self, day: u8, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.0
+            .iter()
+            .filter(move |a| a.day == day && a.part == part.number())
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    /// The history file is not valid.
+    History(PathBuf, String),
+    Input(InputError),
+}
+
+impl From<InputError> for SubmitError {
+    fn from(err: InputError) -> Self {
+        SubmitError::Input(err)
+    }
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "not submitted, {refusal}"),
+            SubmitError::History(path, err) => write!(f, "{}: {err}", path.display()),
+            SubmitError::Input(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Submits the answer unless the history rules it out and records the attempt.
+pub fn submit(
+    config: &Config,
+    day: &Day,
+    part: Part,
+    answer: &Answer,
+) -> Result<Verdict, SubmitError> {
+    let path = config.cache_dir.join(HISTORY_FILE);
+    let mut history = History::load(&path)?;
+
+    let now = unix_now();
+    history
+        .check(day.number, part, answer, now)
+        .map_err(SubmitError::Refused)?;
+
+    let session = config
+        .session
+        .as_deref()
+        .ok_or(InputError::MissingSession)?;
+    let url = format!(
+        "{}/2023/day/{}/answer",
+        config.base_url.trim_end_matches('/'),
+        day.number
+    );
+
+    let response = ureq::post(&url)
+        .set("Cookie", &format!("session={session}"))
+        .set("User-Agent", crate::input::USER_AGENT)
+        .send_form(&[
+            ("level", &part.number().to_string()),
+            ("answer", answer.as_str()),
+        ])
+        .map_err(|err| InputError::Http(err.to_string()))?
+        .into_string()
+        .map_err(|err| InputError::Http(format!("{url}: {err}")))?;
+
+    let (verdict, cooldown) = parse_response(&response);
+    history.0.push(Attempt {
+        day: day.number,
+        part: part.number(),
+        answer: answer.to_string(),
+        verdict,
+        submitted_at: now,
+        wait_until: now + cooldown,
+    });
+
+    fs::create_dir_all(&config.cache_dir)
+        .map_err(|err| InputError::Io(config.cache_dir.clone(), err))?;
+    history.save(&path)?;
+
+    Ok(verdict)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        registry,
+        test_util::{config, stub_server},
+    };
+
+    fn attempt(answer: &str, verdict: Verdict, wait_until: u64) -> Attempt {
+        Attempt {
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            verdict,
+            submitted_at: 0,
+            wait_until,
+        }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = [
+            ("<p>That's the right answer! You are one gold star closer.</p>", Verdict::Correct, 0),
+            (
+                "<p>That's not the right answer; your answer is too high. Please wait one minute before trying again.</p>",
+                Verdict::TooHigh,
+                60,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too low. Please wait 5 minutes before trying again.</p>",
+                Verdict::TooLow,
+                300,
+            ),
+            ("<p>That's not the right answer.</p>", Verdict::Incorrect, 60),
+            (
+                "<p>You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 23s left to wait.</p>",
+                Verdict::Wait(83),
+                83,
+            ),
+            (
+                "<p>You don't seem to be solving the right level. Did you already complete it?</p>",
+                Verdict::WrongLevel,
+                0,
+            ),
+            ("<p>Something else</p>", Verdict::Unknown, 0),
+        ];
+
+        for (html, verdict, cooldown) in cases {
+            assert_eq!(parse_response(html), (verdict, cooldown), "{html}");
+        }
+        // Units are split by character, not by byte
+        assert_eq!(parse_cooldown("you have 5½ left to wait"), None);
+        assert_eq!(parse_cooldown("you have 2h 5s left to wait"), Some(7205));
+    }
+
+    #[test]
+    fn test_check() {
+        let history = History(vec![
+            attempt("100", Verdict::TooHigh, 60),
+            attempt("10", Verdict::TooLow, 120),
+            attempt("abc", Verdict::Incorrect, 180),
+        ]);
+
+        let check = |answer: &str, now| history.check(1, Part::One, &answer.into(), now);
+        assert_eq!(check("50", 200), Ok(()));
+        assert_eq!(check("50", 150), Err(Refusal::Cooldown(30)));
+        assert_eq!(
+            check("abc", 200),
+            Err(Refusal::KnownWrong(Verdict::Incorrect))
+        );
+        assert_eq!(check("120", 200), Err(Refusal::TooHigh(100)));
+        assert_eq!(check("5", 200), Err(Refusal::TooLow(10)));
+
+        // Other parts are not affected except for the cooldown
+        assert_eq!(history.check(1, Part::Two, &"100".into(), 200), Ok(()));
+
+        let history = History(vec![attempt("42", Verdict::Correct, 0)]);
+        assert_eq!(
+            history.check(1, Part::One, &"43".into(), 200),
+            Err(Refusal::Solved("42".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, server) = stub_server("200 OK", "<p>That's not the right answer.</p>");
+        let (_cache_dir, config) = config(url);
+        let day = registry::find(2).unwrap();
+
+        let verdict = submit(&config, day, Part::Two, &"2286".into()).unwrap();
+        assert_eq!(verdict, Verdict::Incorrect);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2023/day/2/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=2286"));
+
+        let history = History::load(&config.cache_dir.join(HISTORY_FILE)).unwrap();
+        assert_eq!(history.0.len(), 1);
+        assert_eq!(history.0[0].verdict, Verdict::Incorrect);
+
+        // The stub is gone, so this must be refused without a request
+        let err = submit(&config, day, Part::Two, &"2286".into()).unwrap_err();
+        assert!(matches!(
+            err,
+            SubmitError::Refused(Refusal::KnownWrong(Verdict::Incorrect))
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers shared by the tests of the HTTP clients.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+};
+
+use tempfile::TempDir;
+
+use crate::input::Config;
+
+/// Serves a single request with `body` and returns the URL of the server and
+/// the received request.
+pub fn stub_server(status: &'static str, body: &'static str) -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let mut request = String::new();
+        let mut reader = BufReader::new(&mut stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = length.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+        }
+
+        let mut content = vec![0; content_length];
+        reader.read_exact(&mut content).unwrap();
+        request.push_str("\r\n");
+        request.push_str(&String::from_utf8(content).unwrap());
+
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+
+        request
+    });
+
+    (url, handle)
+}
+
+/// Config with an empty cache, which is removed when the guard is dropped.
+pub fn config(base_url: String) -> (TempDir, Config) {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        cache_dir: cache_dir.path().to_path_buf(),
+        session: Some("secret".to_string()),
+        base_url,
+    };
+
+    (cache_dir, config)
+}
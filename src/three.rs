@@ -1,55 +1,154 @@
-use std::collections::HashMap;
+use std::collections::BTreeSet;
 
-fn part_one(input: &str) -> i32 {
-    let (symbols, values) = parse_input(input);
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::{
+    error::ParseError,
+    grid::{Adjacency, Grid, Point},
+    solver::{Answer, Solver},
+};
+
+pub struct Three;
+
+impl Solver for Three {
+    type Parsed = (Symbols, Vec<Value>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part_one((symbols, values): &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(symbols, values, Adjacency::default()).into())
+    }
+
+    fn part_two((symbols, _): &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(symbols).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> i32 {
+    part_one_with(input, Adjacency::default())
+}
+
+pub fn part_two(input: &str) -> i32 {
+    part_two_with(input, Adjacency::default())
+}
+
+/// Part one with numbers counting as part numbers by the given adjacency.
+pub fn part_one_with(input: &str, adjacency: Adjacency) -> i32 {
+    let (symbols, values) = parse_with(input, adjacency);
+
+    solve_one(&symbols, &values, adjacency)
+}
+
+/// Part two with numbers counting as part numbers by the given adjacency.
+pub fn part_two_with(input: &str, adjacency: Adjacency) -> i32 {
+    let (symbols, _) = parse_with(input, adjacency);
+
+    solve_two(&symbols)
+}
+
+fn solve_one(symbols: &Symbols, values: &[Value], adjacency: Adjacency) -> i32 {
     let values: Vec<i32> = values
         .iter()
-        .filter(|v| v.is_adjacent(&symbols))
+        .filter(|v| v.is_adjacent(symbols, adjacency))
         .map(|v| v.value())
         .collect();
 
     values.iter().sum()
 }
 
-fn parse_input(input: &str) -> (Symbols, Vec<Value>) {
+fn solve_two(symbols: &Symbols) -> i32 {
+    symbols
+        .iter()
+        .filter_map(|(_, s)| s.as_ref()?.gear_ratio())
+        .sum()
+}
+
+pub fn parse_input(input: &str) -> (Symbols, Vec<Value>) {
+    parse_with(input, Adjacency::default())
+}
+
+/// Parses the schematic and fills the part numbers of the symbols using the
+/// given adjacency.
+pub fn parse_with(input: &str, adjacency: Adjacency) -> (Symbols, Vec<Value>) {
+    parse_schematic(
+        input,
+        &Options {
+            adjacency,
+            ..Options::default()
+        },
+    )
+}
+
+/// How [`parse_schematic`] reads a schematic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub adjacency: Adjacency,
+    /// Characters of empty cells besides whitespace.
+    pub blanks: Vec<char>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            adjacency: Adjacency::default(),
+            blanks: vec!['.'],
+        }
+    }
+}
+
+impl Options {
+    fn is_blank(&self, grapheme: &str) -> bool {
+        let mut chars = grapheme.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.is_whitespace() || self.blanks.contains(&c),
+            _ => false,
+        }
+    }
+}
+
+/// Parses the schematic and fills the part numbers of the symbols.
+///
+/// Every grapheme is one column, so multi-byte characters and combining marks
+/// do not shift the columns after them; a symbol keeps the first character
+/// of its grapheme. Line endings and trailing whitespace are dropped, other
+/// whitespace is blank.
+pub fn parse_schematic(input: &str, options: &Options) -> (Symbols, Vec<Value>) {
+    let lines: Vec<Vec<&str>> = input
+        .lines()
+        .map(|l| l.trim_end().graphemes(true).collect())
+        .collect();
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+
     let mut values = vec![];
-    let mut symbols = Symbols::new();
+    let mut symbols = Symbols::from_fn(width, lines.len(), |_| None);
 
-    for (y, line) in input.lines().enumerate() {
+    for (y, line) in lines.iter().enumerate() {
         let mut value = Value::new();
 
-        for (x, c) in line.chars().enumerate() {
+        for (x, &grapheme) in line.iter().enumerate() {
             // Add digit to number
-            if c.is_ascii_digit() {
-                let number = c.to_digit(10).unwrap() as i32;
+            if let &[digit @ b'0'..=b'9'] = grapheme.as_bytes() {
                 let number = Digit {
-                    value: number,
-                    coordinate: Coordinate::new(x as isize, y as isize),
+                    value: (digit - b'0') as i32,
+                    coordinate: Point::new(x, y),
                 };
                 value.add(number);
                 continue;
             }
 
-            if c == '.' {
-                if !value.is_new() {
-                    values.push(value);
-                    value = Value::new();
-                }
-                continue;
-            }
-
             if !value.is_new() {
                 values.push(value);
                 value = Value::new();
             }
-            symbols.insert(
-                Coordinate::new(x as isize, y as isize),
-                Symbol {
-                    symbol: c,
+
+            if !options.is_blank(grapheme) {
+                symbols[Point::new(x, y)] = Some(Symbol {
+                    symbol: grapheme.chars().next().unwrap(),
                     part_numbers: vec![],
-                },
-            );
+                });
+            }
         }
 
         if !value.is_new() {
@@ -57,12 +156,19 @@ fn parse_input(input: &str) -> (Symbols, Vec<Value>) {
         }
     }
 
+    for value in &values {
+        for point in value.adjacent_symbols(&symbols, options.adjacency) {
+            let symbol = symbols[point].as_mut().unwrap();
+            symbol.part_numbers.push(value.value());
+        }
+    }
+
     (symbols, values)
 }
 
-#[derive(Debug)]
-struct Value {
-    digits: Vec<Digit>,
+#[derive(Debug, Default)]
+pub struct Value {
+    pub digits: Vec<Digit>,
 }
 
 impl Value {
@@ -78,14 +184,22 @@ impl Value {
         self.digits.is_empty()
     }
 
-    pub fn is_adjacent(&self, symbols: &Symbols) -> bool {
-        for number in &self.digits {
-            if number.coordinate.is_adjacent(symbols) {
-                return true;
-            }
-        }
+    pub fn is_adjacent(&self, symbols: &Symbols, adjacency: Adjacency) -> bool {
+        self.digits.iter().any(|d| {
+            symbols
+                .adjacent(d.coordinate, adjacency)
+                .any(|(_, s)| s.is_some())
+        })
+    }
 
-        false
+    /// Positions of every symbol next to any digit, each listed once.
+    pub fn adjacent_symbols(&self, symbols: &Symbols, adjacency: Adjacency) -> BTreeSet<Point> {
+        self.digits
+            .iter()
+            .flat_map(|d| symbols.adjacent(d.coordinate, adjacency))
+            .filter(|(_, s)| s.is_some())
+            .map(|(point, _)| point)
+            .collect()
     }
 
     pub fn value(&self) -> i32 {
@@ -100,55 +214,35 @@ impl Value {
 }
 
 #[derive(Debug)]
-struct Digit {
-    value: i32,
-    coordinate: Coordinate,
+pub struct Digit {
+    pub value: i32,
+    pub coordinate: Point,
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
-struct Coordinate {
-    x: isize,
-    y: isize,
+#[derive(Debug)]
+pub struct Symbol {
+    pub symbol: char,
+    /// Numbers adjacent to the symbol, filled by [`parse_input`].
+    pub part_numbers: Vec<i32>,
 }
 
-impl Coordinate {
-    pub fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-    pub fn is_adjacent(&self, symbols: &Symbols) -> bool {
-        let x = self.x;
-        let y = self.y;
-
-        // Vertical
-        let top = symbols.contains_key(&Coordinate::new(x, y - 1));
-        let bottom = symbols.contains_key(&Coordinate::new(x, y + 1));
-
-        // Horizontal
-        let left = symbols.contains_key(&Coordinate::new(x - 1, y));
-        let right = symbols.contains_key(&Coordinate::new(x + 1, y));
-
-        // Diagonal
-        let top_left = symbols.contains_key(&Coordinate::new(x - 1, y - 1));
-        let bottom_left = symbols.contains_key(&Coordinate::new(x - 1, y + 1));
-
-        let top_right = symbols.contains_key(&Coordinate::new(x + 1, y - 1));
-        let bottom_right = symbols.contains_key(&Coordinate::new(x + 1, y + 1));
-
-        top || bottom || left || right || top_left || bottom_left || top_right || bottom_right
+impl Symbol {
+    /// Product of the two part numbers of a gear, a `*` next to exactly two.
+    pub fn gear_ratio(&self) -> Option<i32> {
+        match self.part_numbers[..] {
+            [a, b] if self.symbol == '*' => Some(a * b),
+            _ => None,
+        }
     }
 }
 
-struct Symbol {
-    symbol: char,
-    part_numbers: Vec<i32>,
-}
-type Symbols = HashMap<Coordinate, Symbol>;
+/// Symbols of the schematic, empty cells and digits are `None`.
+pub type Symbols = Grid<Option<Symbol>>;
 
 #[cfg(test)]
 mod test {
     use super::*;
-
-    const INPUT: &str = include_str!("../assets/three.txt");
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
     #[test]
     fn test_one() {
@@ -168,7 +262,123 @@ mod test {
 
     #[test]
     fn solution_one() {
-        let result = part_one(INPUT);
-        dbg!(result);
+        let Some(input) = cached_input("three") else {
+            return;
+        };
+        let result = part_one(&input);
+        assert_eq!(result.to_string(), expected_answer("three", Part::One));
+    }
+
+    #[test]
+    fn test_two() {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+        assert_eq!(part_two(input), 467835);
+    }
+
+    #[test]
+    fn test_ragged_lines() {
+        let (symbols, values) = parse_input("12\n...*\n3");
+
+        assert_eq!((symbols.width(), symbols.height()), (4, 3));
+        assert_eq!(symbols[Point::new(3, 1)].as_ref().unwrap().symbol, '*');
+        assert!(!values[0].is_adjacent(&symbols, Adjacency::default()));
+        assert!(values[1]
+            .adjacent_symbols(&symbols, Adjacency::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_crlf() {
+        let input = "467..114..\r\n...*......\r\n..35..633.\r\n";
+        let (symbols, _) = parse_input(input);
+
+        assert_eq!((symbols.width(), symbols.height()), (10, 3));
+        assert_eq!(symbols.iter().filter(|(_, s)| s.is_some()).count(), 1);
+        assert_eq!(part_one(input), 467 + 35);
+    }
+
+    #[test]
+    fn test_whitespace() {
+        // A number ending a line is no longer next to a trailing `\t`
+        let (symbols, values) = parse_input("..12 \t\n.. 3\n");
+
+        assert_eq!(symbols.width(), 4);
+        assert!(symbols.iter().all(|(_, s)| s.is_none()));
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn test_blanks() {
+        let input = "12_~\n__*_\n3__4";
+        let options = Options {
+            blanks: vec!['_', '~'],
+            ..Options::default()
+        };
+        let (symbols, values) = parse_schematic(input, &options);
+
+        assert_eq!(symbols.iter().filter(|(_, s)| s.is_some()).count(), 1);
+        assert_eq!(solve_one(&symbols, &values, options.adjacency), 12 + 4);
+        // By default the underscores are symbols, so the 3 is a part number
+        assert_eq!(part_one(input), 12 + 3 + 4);
+        assert_eq!(
+            parse_input(input)
+                .0
+                .iter()
+                .filter(|(_, s)| s.is_some())
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_graphemes() {
+        // `é` as two code points, `€` as three bytes, each one column
+        let input = "e\u{301}.5\n..€.\n7...";
+        let (symbols, values) = parse_input(input);
+
+        assert_eq!(symbols.width(), 4);
+        assert_eq!(symbols[Point::new(0, 0)].as_ref().unwrap().symbol, 'e');
+        assert_eq!(symbols[Point::new(2, 1)].as_ref().unwrap().symbol, '€');
+        assert_eq!(values[0].digits[0].coordinate, Point::new(2, 0));
+        assert_eq!(part_one(input), 5);
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let input = "1.*.
+....
+2..3
+*...";
+        let part_one = |adjacency: &str| part_one_with(input, adjacency.parse().unwrap());
+
+        assert_eq!(part_one("4"), 2);
+        assert_eq!(part_one("8"), 2);
+        assert_eq!(part_one("radius-2"), 6);
+        // The 1 wraps around to the `*` below it, the 3 only diagonally
+        assert_eq!(part_one("4-toroidal"), 3);
+        assert_eq!(part_one("8-toroidal"), 6);
+
+        // The bottom `*` is only a gear when wrapping orthogonally
+        assert_eq!(part_two_with(input, "4-toroidal".parse().unwrap()), 2);
+        assert_eq!(part_two_with(input, "8-toroidal".parse().unwrap()), 0);
+    }
+
+    #[test]
+    fn solution_two() {
+        let Some(input) = cached_input("three") else {
+            return;
+        };
+        let result = part_two(&input);
+        assert_eq!(result.to_string(), expected_answer("three", Part::Two));
     }
 }
@@ -0,0 +1,172 @@
+use crate::{
+    grid::{Adjacency, Grid, Point},
+    three::{Symbols, Value},
+};
+
+const RESET: &str = "\x1b[0m";
+
+/// Meaning of the markers of [`Style::Plain`].
+pub const LEGEND: &str = "p part number, x not a part number, s symbol, g gear candidate, G gear";
+
+/// How the cells of the schematic are told apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// ANSI colours, for terminals.
+    Ansi,
+    /// A line of markers below every line of the schematic.
+    Plain,
+}
+
+/// Kind of a non-blank cell of the schematic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    PartNumber(char),
+    /// Digit of a number not adjacent to any symbol.
+    Number(char),
+    Symbol(char),
+    /// A `*` not adjacent to exactly two part numbers.
+    GearCandidate,
+    Gear,
+}
+
+impl Cell {
+    fn char(&self) -> char {
+        match *self {
+            Cell::PartNumber(c) | Cell::Number(c) | Cell::Symbol(c) => c,
+            Cell::GearCandidate | Cell::Gear => '*',
+        }
+    }
+
+    fn marker(&self) -> char {
+        match self {
+            Cell::PartNumber(_) => 'p',
+            Cell::Number(_) => 'x',
+            Cell::Symbol(_) => 's',
+            Cell::GearCandidate => 'g',
+            Cell::Gear => 'G',
+        }
+    }
+
+    fn colour(&self) -> &'static str {
+        match self {
+            Cell::PartNumber(_) => "\x1b[32m",
+            Cell::Number(_) => "\x1b[31m",
+            Cell::Symbol(_) => "\x1b[1;33m",
+            Cell::GearCandidate => "\x1b[35m",
+            Cell::Gear => "\x1b[1;35m",
+        }
+    }
+}
+
+/// Classifies every cell of a schematic parsed with the same adjacency.
+pub fn cells(symbols: &Symbols, values: &[Value], adjacency: Adjacency) -> Grid<Option<Cell>> {
+    let mut cells = Grid::from_fn(symbols.width(), symbols.height(), |point| {
+        let symbol = symbols[point].as_ref()?;
+        Some(match symbol.symbol {
+            '*' if symbol.gear_ratio().is_some() => Cell::Gear,
+            '*' => Cell::GearCandidate,
+            c => Cell::Symbol(c),
+        })
+    });
+
+    for value in values {
+        let part = value.is_adjacent(symbols, adjacency);
+        for digit in &value.digits {
+            let c = char::from_digit(digit.value as u32, 10).unwrap();
+            cells[digit.coordinate] = Some(if part {
+                Cell::PartNumber(c)
+            } else {
+                Cell::Number(c)
+            });
+        }
+    }
+
+    cells
+}
+
+/// Renders the schematic, blank cells as `.`.
+pub fn render(symbols: &Symbols, values: &[Value], adjacency: Adjacency, style: Style) -> String {
+    let cells = cells(symbols, values, adjacency);
+    let mut output = String::new();
+
+    for y in 0..cells.height() {
+        let row = (0..cells.width()).map(|x| cells[Point::new(x, y)]);
+
+        match style {
+            Style::Ansi => {
+                let mut colour = None;
+                for cell in row {
+                    let next = cell.map(|c| c.colour());
+                    if next != colour {
+                        output.push_str(next.unwrap_or(RESET));
+                        colour = next;
+                    }
+                    output.push(cell.map_or('.', |c| c.char()));
+                }
+                if colour.is_some() {
+                    output.push_str(RESET);
+                }
+            }
+            Style::Plain => {
+                let markers: String = row
+                    .clone()
+                    .map(|cell| cell.map_or(' ', |c| c.marker()))
+                    .collect();
+                output.extend(row.map(|cell| cell.map_or('.', |c| c.char())));
+                output.push('\n');
+                output.push_str(markers.trim_end());
+            }
+        }
+
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::three::parse_input;
+
+    fn render_input(input: &str, style: Style) -> String {
+        let (symbols, values) = parse_input(input);
+
+        render(&symbols, &values, Adjacency::default(), style)
+    }
+
+    #[test]
+    fn test_plain() {
+        let input = "467..114..
+...*......
+..35..633.
+......#...
+617*......";
+
+        assert_eq!(
+            render_input(input, Style::Plain),
+            "467..114..
+ppp  xxx
+...*......
+   G
+..35..633.
+  pp  ppp
+......#...
+      s
+617*......
+pppg
+"
+        );
+    }
+
+    #[test]
+    fn test_ansi() {
+        assert_eq!(
+            render_input("1.*\n.#.\n...\n5..", Style::Ansi),
+            "\x1b[32m1\x1b[0m.\x1b[35m*\x1b[0m\n\
+             .\x1b[1;33m#\x1b[0m.\n\
+             ...\n\
+             \x1b[31m5\x1b[0m..\n"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::{
+    grid::{Adjacency, Point},
+    three::{Symbols, Value},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AdjacentSymbol {
+    pub symbol: char,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for AdjacentSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.symbol, Point::new(self.x, self.y))
+    }
+}
+
+/// How many symbols a number touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag {
+    /// Not a part number.
+    NoSymbol,
+    Single,
+    /// Counted once in part one, but attached to every symbol in part two.
+    Multiple,
+}
+
+impl Flag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flag::NoSymbol => "no_symbol",
+            Flag::Single => "single",
+            Flag::Multiple => "multiple",
+        }
+    }
+}
+
+/// Symbols adjacent to a number of the schematic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Attribution {
+    pub number: i32,
+    /// Position of the first digit.
+    pub x: usize,
+    pub y: usize,
+    pub symbols: Vec<AdjacentSymbol>,
+    pub flag: Flag,
+}
+
+/// Attributions of every number, in reading order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Report(pub Vec<Attribution>);
+
+impl Report {
+    /// Numbers flagged as touching no symbol or several.
+    pub fn flagged(&self) -> impl Iterator<Item = &Attribution> {
+        self.0.iter().filter(|a| a.flag != Flag::Single)
+    }
+
+    /// One row per number, the symbols are `;` separated `symbol x y`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("number,x,y,flag,symbols\n");
+
+        for attribution in &self.0 {
+            let symbols: Vec<String> = attribution
+                .symbols
+                .iter()
+                .map(|s| format!("{} {} {}", s.symbol, s.x, s.y))
+                .collect();
+
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                attribution.number,
+                attribution.x,
+                attribution.y,
+                attribution.flag.name(),
+                csv_field(&symbols.join(";"))
+            )
+            .unwrap();
+        }
+
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is always serializable")
+    }
+}
+
+/// Quotes fields containing separators, quotes or line breaks.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for attribution in &self.0 {
+            let position = Point::new(attribution.x, attribution.y);
+            write!(f, "{} at {position}: ", attribution.number)?;
+
+            if attribution.symbols.is_empty() {
+                writeln!(f, "no symbol")?;
+                continue;
+            }
+
+            let symbols: Vec<String> = attribution.symbols.iter().map(|s| s.to_string()).collect();
+            write!(f, "{}", symbols.join(", "))?;
+            if attribution.flag == Flag::Multiple {
+                write!(f, " (multiple)")?;
+            }
+            writeln!(f)?;
+        }
+
+        let none = self.0.iter().filter(|a| a.flag == Flag::NoSymbol).count();
+        let multiple = self.0.iter().filter(|a| a.flag == Flag::Multiple).count();
+        write!(
+            f,
+            "{} numbers, {none} without a symbol, {multiple} with multiple",
+            self.0.len()
+        )
+    }
+}
+
+/// Lists the symbols adjacent to every number of a parsed schematic.
+pub fn attribute(symbols: &Symbols, values: &[Value], adjacency: Adjacency) -> Report {
+    let attributions = values
+        .iter()
+        .filter_map(|value| {
+            let first = value.digits.first()?.coordinate;
+            let adjacent: Vec<AdjacentSymbol> = value
+                .adjacent_symbols(symbols, adjacency)
+                .into_iter()
+                .map(|point| AdjacentSymbol {
+                    symbol: symbols[point].as_ref().unwrap().symbol,
+                    x: point.x,
+                    y: point.y,
+                })
+                .collect();
+
+            let flag = match adjacent.len() {
+                0 => Flag::NoSymbol,
+                1 => Flag::Single,
+                _ => Flag::Multiple,
+            };
+
+            Some(Attribution {
+                number: value.value(),
+                x: first.x,
+                y: first.y,
+                symbols: adjacent,
+                flag,
+            })
+        })
+        .collect();
+
+    Report(attributions)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::three::parse_input;
+
+    const SAMPLE_INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn report(input: &str) -> Report {
+        let (symbols, values) = parse_input(input);
+
+        attribute(&symbols, &values, Adjacency::default())
+    }
+
+    #[test]
+    fn test_attribute() {
+        let report = report(SAMPLE_INPUT);
+
+        assert_eq!(report.0.len(), 10);
+        assert_eq!(
+            report.0[0],
+            Attribution {
+                number: 467,
+                x: 0,
+                y: 0,
+                symbols: vec![AdjacentSymbol {
+                    symbol: '*',
+                    x: 3,
+                    y: 1
+                }],
+                flag: Flag::Single,
+            }
+        );
+
+        let flagged: Vec<(i32, Flag)> = report.flagged().map(|a| (a.number, a.flag)).collect();
+        assert_eq!(flagged, [(114, Flag::NoSymbol), (58, Flag::NoSymbol)]);
+        assert!(report
+            .to_string()
+            .ends_with("10 numbers, 2 without a symbol, 0 with multiple"));
+    }
+
+    #[test]
+    fn test_multiple() {
+        let report = report("#12\n..,");
+
+        assert_eq!(report.0[0].flag, Flag::Multiple);
+        assert_eq!(
+            report.to_string(),
+            "12 at (1, 0): # at (0, 0), , at (2, 1) (multiple)\n\
+             1 numbers, 0 without a symbol, 1 with multiple"
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let report = report("#12\n..,\n5..");
+
+        assert_eq!(
+            report.to_csv(),
+            "number,x,y,flag,symbols\n12,1,0,multiple,\"# 0 0;, 2 1\"\n5,0,2,no_symbol,\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json[0]["symbols"][1]["symbol"], ",");
+        assert_eq!(json[1]["flag"], "no_symbol");
+    }
+}
@@ -1,102 +1,394 @@
-fn part_one(input: &str) -> i32 {
-    input
-        .lines()
-        .map(Game::from)
-        .filter(|g| g.is_possible())
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{column_of, end_of, parse_lines, ParseError},
+    solver::{Answer, Solver},
+};
+
+const DAY: u8 = 2;
+
+/// Colours of the puzzle's cubes.
+pub const COLOURS: &[&str] = &["red", "green", "blue"];
+
+pub struct Two;
+
+impl Solver for Two {
+    type Parsed = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(games: &Self::Parsed) -> Result<Answer, ParseError> {
+        Ok(solve_one(games).into())
+    }
+
+    fn part_two(games: &Self::Parsed) -> Option<Result<Answer, ParseError>> {
+        Some(Ok(solve_two(games).into()))
+    }
+}
+
+pub fn part_one(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_one(&parse_input(input)?))
+}
+
+pub fn part_two(input: &str) -> Result<i32, ParseError> {
+    Ok(solve_two(&parse_input(input)?))
+}
+
+fn solve_one(games: &[Game]) -> i32 {
+    let bag = Bag::default();
+
+    games
+        .iter()
+        .filter(|g| g.is_possible(&bag))
         .map(|g| g.id)
         .sum()
 }
 
-fn part_two(input: &str) -> i32 {
-    input.lines().map(Game::from).map(|g| g.power()).sum()
+fn solve_two(games: &[Game]) -> i32 {
+    games.iter().map(|g| g.power()).sum()
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse_lines(input)
 }
 
-#[derive(Debug)]
-struct Game {
-    id: i32,
-    sets: Vec<Set>,
+/// Checks every game against the bag, in the order of the games.
+pub fn feasibility<'a>(games: &'a [Game], bag: &Bag) -> Vec<(&'a Game, Option<Violation>)> {
+    games
+        .iter()
+        .map(|game| (game, game.first_violation(bag)))
+        .collect()
+}
+
+/// Number of cubes of each colour in the bag, the puzzle's bag by default.
+///
+/// Colours missing from the bag have no cubes.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Bag(pub BTreeMap<String, i32>);
+
+impl Bag {
+    pub fn count(&self, colour: &str) -> i32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, colour: &str, count: i32) {
+        self.0.insert(colour.to_string(), count);
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Self(BTreeMap::from([
+            ("red".to_string(), 12),
+            ("green".to_string(), 13),
+            ("blue".to_string(), 14),
+        ]))
+    }
+}
+
+impl FromStr for Bag {
+    type Err = toml::de::Error;
+
+    /// Colours missing from the configuration keep the puzzle's counts.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bag = Bag::default();
+        bag.0.extend(toml::from_str::<Bag>(s)?.0);
+
+        Ok(bag)
+    }
+}
+
+/// First set of a game drawing more cubes of a colour than the bag holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// 1-based index of the set within the game.
+    pub set: usize,
+    pub colour: String,
+    pub count: i32,
+    pub limit: i32,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "set {} draws {} {} cubes, the bag holds {}",
+            self.set, self.count, self.colour, self.limit
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Game {
+    pub id: i32,
+    pub sets: Vec<Set>,
 }
 
 impl Game {
-    pub fn is_possible(&self) -> bool {
-        for set in self.sets.iter() {
-            if set.red > 12 || set.green > 13 || set.blue > 14 {
-                return false;
-            }
+    pub fn is_possible(&self, bag: &Bag) -> bool {
+        self.first_violation(bag).is_none()
+    }
+
+    pub fn first_violation(&self, bag: &Bag) -> Option<Violation> {
+        self.sets.iter().enumerate().find_map(|(idx, set)| {
+            set.0
+                .iter()
+                .find(|&(colour, &count)| count > bag.count(colour))
+                .map(|(colour, &count)| Violation {
+                    set: idx + 1,
+                    colour: colour.clone(),
+                    count,
+                    limit: bag.count(colour),
+                })
+        })
+    }
+
+    /// Smallest bag the game is possible with.
+    pub fn minimum_bag(&self) -> Bag {
+        let mut bag = Bag(BTreeMap::new());
+        for (colour, &count) in self.sets.iter().flat_map(|s| &s.0) {
+            let minimum = bag.0.entry(colour.clone()).or_default();
+            *minimum = count.max(*minimum);
         }
 
-        true
+        bag
     }
 
+    /// Power over the puzzle's colours, zero when one of them is never drawn.
     pub fn power(&self) -> i32 {
-        let sets = &self.sets;
+        self.power_of(COLOURS)
+    }
+
+    /// Product of the minimum counts of the given colours.
+    pub fn power_of(&self, colours: &[&str]) -> i32 {
+        let bag = self.minimum_bag();
+
+        colours.iter().map(|colour| bag.count(colour)).product()
+    }
+}
+
+/// Cubes drawn at once, keyed by colour.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Set(pub BTreeMap<String, i32>);
+
+impl Set {
+    pub fn count(&self, colour: &str) -> i32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+}
+
+/// Games as a JSON array, sets map colours to counts.
+pub fn to_json(games: &[Game]) -> String {
+    serde_json::to_string_pretty(games).expect("games are always serializable")
+}
+
+/// Games from a JSON array, rejecting games the puzzle format cannot hold.
+pub fn from_json(json: &str) -> Result<Vec<Game>, serde_json::Error> {
+    let games: Vec<Game> = serde_json::from_str(json)?;
+
+    for game in &games {
+        check_game(game).map_err(serde::de::Error::custom)?;
+    }
 
-        let red = sets.iter().map(|s| s.red).max().unwrap();
-        let green = sets.iter().map(|s| s.green).max().unwrap();
-        let blue = sets.iter().map(|s| s.blue).max().unwrap();
+    Ok(games)
+}
+
+/// Checks that the game displays as a line [`Game::from_str`] reads back.
+fn check_game(game: &Game) -> Result<(), String> {
+    if game.sets.is_empty() {
+        return Err(format!("game {} has no sets", game.id));
+    }
+
+    for (idx, set) in game.sets.iter().enumerate() {
+        if set.0.is_empty() {
+            return Err(format!("game {} set {} has no cubes", game.id, idx + 1));
+        }
+
+        let invalid = |c: char| c.is_whitespace() || c == ',' || c == ';';
+        if let Some(colour) = set.0.keys().find(|c| c.is_empty() || c.contains(invalid)) {
+            return Err(format!("game {} has an invalid colour `{colour}`", game.id));
+        }
+    }
 
-        red * green * blue
+    Ok(())
+}
+
+/// Canonical form `Game 1: 4 red, 3 blue; 2 green` with colours in
+/// alphabetical order.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+
+        for (idx, set) in self.sets.iter().enumerate() {
+            let separator = if idx == 0 { " " } else { "; " };
+            write!(f, "{separator}{set}")?;
+        }
+
+        Ok(())
     }
 }
 
-#[derive(Debug)]
-struct Set {
-    red: i32,
-    green: i32,
-    blue: i32,
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (colour, count)) in self.0.iter().enumerate() {
+            let separator = if idx == 0 { "" } else { ", " };
+            write!(f, "{separator}{count} {colour}")?;
+        }
+
+        Ok(())
+    }
 }
 
-impl From<&str> for Game {
-    fn from(value: &str) -> Self {
-        let mut split = value.split(':');
+impl FromStr for Game {
+    type Err = ParseError;
 
-        let id: i32 = split
-            .next()
-            .unwrap()
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (header, sets) = value
+            .split_once(':')
+            .ok_or_else(|| ParseError::at(DAY, value, end_of(value), "`:` after the game id"))?;
+
+        let id = header
             .split_whitespace()
-            .last()
-            .unwrap()
+            .next_back()
+            .ok_or_else(|| ParseError::at(DAY, value, header, "a game id"))?;
+        let id: i32 = id
             .parse()
-            .unwrap();
+            .map_err(|_| ParseError::at(DAY, value, id, "a game id"))?;
 
-        let sets: Vec<Set> = split.next().unwrap().split(';').map(Set::from).collect();
+        let sets = sets
+            .split(';')
+            .map(|set| {
+                set.parse()
+                    .map_err(|e: ParseError| e.shift_column(column_of(value, set)))
+            })
+            .collect::<Result<Vec<Set>, _>>()?;
 
-        Self { id, sets }
+        Ok(Self { id, sets })
     }
 }
 
-impl From<&str> for Set {
-    fn from(value: &str) -> Self {
+impl FromStr for Set {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         let cubes = value.split(',');
 
-        let mut set = Self {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
+        let mut set = Self::default();
 
         for cube in cubes {
             let mut split = cube.split_ascii_whitespace();
-            let value: i32 = split.next().unwrap().parse().unwrap();
-            let color = split.next().unwrap();
-
-            match color {
-                "red" => set.red = value,
-                "green" => set.green = value,
-                "blue" => set.blue = value,
-                _ => panic!("unexpected color"),
+            let count = split
+                .next()
+                .ok_or_else(|| ParseError::at(DAY, value, end_of(cube), "a cube count"))?;
+            let count: i32 = count
+                .parse()
+                .map_err(|_| ParseError::at(DAY, value, count, "a cube count"))?;
+            let colour = split
+                .next()
+                .ok_or_else(|| ParseError::at(DAY, value, end_of(cube), "a cube colour"))?;
+            if let Some(extra) = split.next() {
+                return Err(ParseError::at(
+                    DAY,
+                    value,
+                    extra,
+                    "`,` or `;` after the colour",
+                ));
+            }
+
+            if set.0.insert(colour.to_string(), count).is_some() {
+                return Err(ParseError::at(
+                    DAY,
+                    value,
+                    colour,
+                    "every colour once per set",
+                ));
             }
         }
 
-        set
+        Ok(set)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::{input::cached_input, solver::Part, verify::expected_answer};
 
-    const INPUT: &str = include_str!("../assets/two.txt");
+    fn arb_set() -> impl Strategy<Value = Set> {
+        prop::collection::btree_map("[a-z]{1,8}", 0..1000, 1..5).prop_map(Set)
+    }
+
+    fn arb_game() -> impl Strategy<Value = Game> {
+        (0..10_000, prop::collection::vec(arb_set(), 1..6)).prop_map(|(id, sets)| Game { id, sets })
+    }
+
+    /// Games whose displayed form does not read back as the same game.
+    fn arb_invalid_game() -> impl Strategy<Value = Game> {
+        let invalid_set = prop_oneof![
+            Just(Set::default()),
+            ("[a-z]{0,3}[ \t,;][a-z]{0,3}|", 0..1000)
+                .prop_map(|(colour, count)| Set(BTreeMap::from([(colour, count)]))),
+        ];
+        let sets = (
+            prop::collection::vec(arb_set(), 0..3),
+            invalid_set,
+            prop::collection::vec(arb_set(), 0..3),
+        )
+            .prop_map(|(mut before, invalid, after)| {
+                before.push(invalid);
+                before.extend(after);
+                before
+            });
+
+        (0..10_000, prop_oneof![Just(vec![]), sets]).prop_map(|(id, sets)| Game { id, sets })
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_round_trip(game in arb_game()) {
+            prop_assert_eq!(game.to_string().parse::<Game>().unwrap(), game);
+        }
+
+        #[test]
+        fn test_json_round_trip(games in prop::collection::vec(arb_game(), 0..5)) {
+            prop_assert_eq!(from_json(&to_json(&games)).unwrap(), games);
+        }
+
+        #[test]
+        fn test_json_invalid(game in arb_invalid_game()) {
+            let json = to_json(std::slice::from_ref(&game));
+            prop_assert!(from_json(&json).is_err());
+            prop_assert_ne!(game.to_string().parse::<Game>().ok(), Some(game));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let game: Game = "Game 7: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            game.to_string(),
+            "Game 7: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green"
+        );
+        assert_eq!(
+            serde_json::to_string(&game).unwrap(),
+            r#"{"id":7,"sets":[{"blue":3,"red":4},{"blue":6,"green":2,"red":1},{"green":2}]}"#
+        );
+
+        let error = from_json(r#"[{"id":1,"sets":[]}]"#).unwrap_err();
+        assert_eq!(error.to_string(), "game 1 has no sets");
+        let error = from_json(r#"[{"id":1,"sets":[{"red":1},{"dark red":3}]}]"#).unwrap_err();
+        assert_eq!(error.to_string(), "game 1 has an invalid colour `dark red`");
+    }
 
     #[test]
     fn test_one() {
@@ -106,13 +398,16 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(part_one(input), 8)
+        assert_eq!(part_one(input).unwrap(), 8)
     }
 
     #[test]
     fn solution_one() {
-        let solution = part_one(INPUT);
-        dbg!(solution);
+        let Some(input) = cached_input("two") else {
+            return;
+        };
+        let solution = part_one(&input).unwrap();
+        assert_eq!(solution.to_string(), expected_answer("two", Part::One));
     }
 
     #[test]
@@ -123,12 +418,91 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(part_two(input), 2286)
+        assert_eq!(part_two(input).unwrap(), 2286)
     }
 
     #[test]
     fn solution_two() {
-        let solution = part_two(INPUT);
-        dbg!(solution);
+        let Some(input) = cached_input("two") else {
+            return;
+        };
+        let solution = part_two(&input).unwrap();
+        assert_eq!(solution.to_string(), expected_answer("two", Part::Two));
+    }
+
+    #[test]
+    fn test_feasibility() {
+        let games = parse_input(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+        )
+        .unwrap();
+
+        let result = feasibility(&games, &Bag::default());
+        let violations: Vec<(i32, Option<String>)> = result
+            .iter()
+            .map(|(game, violation)| (game.id, violation.as_ref().map(|v| v.to_string())))
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                (1, None),
+                (
+                    3,
+                    Some("set 1 draws 20 red cubes, the bag holds 12".to_string())
+                ),
+                (
+                    4,
+                    Some("set 3 draws 15 blue cubes, the bag holds 14".to_string())
+                ),
+            ]
+        );
+
+        let bag: Bag = "red = 20\nblue = 15".parse().unwrap();
+        assert_eq!(bag.count("green"), 13);
+        assert!(games.iter().all(|g| g.is_possible(&bag)));
+        assert!("red = \"many\"".parse::<Bag>().is_err());
+    }
+
+    #[test]
+    fn test_colours() {
+        let games = parse_input(
+            "Game 1: 3 blue, 4 yellow; 1 red, 2 green
+Game 2: 2 yellow; 5 yellow, 1 blue",
+        )
+        .unwrap();
+        assert_eq!(games[0].sets[0].count("yellow"), 4);
+        assert_eq!(games[0].sets[0].count("red"), 0);
+
+        assert_eq!(games[0].power(), 6);
+        assert_eq!(games[0].power_of(&["red", "green", "blue", "yellow"]), 24);
+        // Game 2 never draws red or green
+        assert_eq!(games[1].power(), 0);
+        assert_eq!(games[1].power_of(&["blue", "yellow"]), 5);
+        assert_eq!(part_two("Game 1: 3 blue, 4 red").unwrap(), 0);
+
+        let mut bag = Bag::default();
+        let violation = games[1].first_violation(&bag).unwrap();
+        assert_eq!((violation.set, violation.colour.as_str()), (1, "yellow"));
+
+        bag.set("yellow", 5);
+        assert!(games.iter().all(|g| g.is_possible(&bag)));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue; 2 red, 3 red";
+
+        let error = parse_input(input).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 26));
+        assert_eq!(error.found, "red");
+
+        let error = parse_input("Game 1: 3 dark red").unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (16, "red"));
+        let error = parse_input("Game 1: 1 red; 3 blue extra, 2 green").unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (23, "extra"));
+        assert_eq!(error.expected, "`,` or `;` after the colour");
     }
 }
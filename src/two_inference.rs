@@ -0,0 +1,337 @@
+use std::fmt;
+
+use crate::two::Game;
+
+/// Upper bound of bag compositions evaluated per game.
+const MAX_CANDIDATES: usize = 1_000_000;
+
+/// Upper bound of cubes in a searched bag, the search keeps the log
+/// factorial of every count up to it.
+pub const MAX_CUBES: i64 = 1_000_000;
+
+/// Search space and confidence level of [`infer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Cubes of each colour considered on top of the minimum bag.
+    pub extra: i32,
+    /// Confidence level of the ranges, between 0 and 1.
+    pub confidence: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            extra: 20,
+            confidence: 0.95,
+        }
+    }
+}
+
+/// Estimated number of cubes of one colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    pub colour: String,
+    /// Most cubes drawn at once, the bag holds at least as many.
+    pub minimum: i32,
+    /// Maximum likelihood estimate.
+    pub likely: i32,
+    pub low: i32,
+    pub high: i32,
+    /// `high` is the end of the searched range, the bag may hold more.
+    pub open: bool,
+}
+
+/// Likely bag composition of a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inference {
+    pub game: i32,
+    /// Estimates of the colours drawn in the game, other colours are most
+    /// likely not in the bag at all.
+    pub estimates: Vec<Estimate>,
+    pub confidence: f64,
+}
+
+impl fmt::Display for Inference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "game {}:", self.game)?;
+
+        for (idx, estimate) in self.estimates.iter().enumerate() {
+            let separator = if idx == 0 { " " } else { ", " };
+            let open = if estimate.open { "+" } else { "" };
+            write!(
+                f,
+                "{separator}{} {} (likely {}, {:.0}% {}..={}{open})",
+                estimate.colour,
+                estimate.minimum,
+                estimate.likely,
+                self.confidence * 100.0,
+                estimate.low,
+                estimate.high,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Game drawing more cubes than can be searched, see [`MAX_CUBES`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooManyCubes {
+    pub game: i32,
+    pub cubes: i64,
+}
+
+impl fmt::Display for TooManyCubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "game {} needs at least {} cubes, at most {MAX_CUBES} can be searched",
+            self.game, self.cubes
+        )
+    }
+}
+
+impl std::error::Error for TooManyCubes {}
+
+/// Estimates the bag of a game from its sets.
+///
+/// Every set is assumed to be drawn without replacement from the full bag,
+/// so the likelihood of a bag is the product of the multivariate
+/// hypergeometric probabilities of the sets. The search covers every bag
+/// between the minimum bag and `extra` more cubes of each colour, shrinking
+/// `extra` when there would be too many bags or cubes. Ties go to the
+/// smaller bag, ranges are profile likelihood intervals.
+pub fn infer(game: &Game, options: &Options) -> Result<Inference, TooManyCubes> {
+    let minimum = game.minimum_bag();
+    let colours: Vec<(&String, i32)> = minimum.0.iter().map(|(c, &n)| (c, n.max(0))).collect();
+
+    let cubes: i64 = colours.iter().map(|&(_, n)| i64::from(n)).sum();
+    if cubes > MAX_CUBES {
+        return Err(TooManyCubes {
+            game: game.id,
+            cubes,
+        });
+    }
+
+    let spare = (MAX_CUBES - cubes) / colours.len().max(1) as i64;
+    let mut extra = i64::from(options.extra.max(0)).min(spare) as i32;
+    while extra > 0 && candidates(colours.len(), extra) > MAX_CANDIDATES {
+        extra -= 1;
+    }
+
+    // Counts of every set in the order of `colours` and their total
+    let sets: Vec<(Vec<i32>, i32)> = game
+        .sets
+        .iter()
+        .map(|set| {
+            let counts: Vec<i32> = colours.iter().map(|(c, _)| set.count(c).max(0)).collect();
+            let total = counts.iter().sum();
+            (counts, total)
+        })
+        .collect();
+
+    let max_total = colours.iter().map(|(_, n)| n + extra).sum::<i32>();
+    let ln_factorials = ln_factorials(max_total as usize);
+    let ln_choose = |n: i32, k: i32| {
+        ln_factorials[n as usize] - ln_factorials[k as usize] - ln_factorials[(n - k) as usize]
+    };
+
+    let mut bag: Vec<i32> = colours.iter().map(|&(_, n)| n).collect();
+    let mut best = (f64::NEG_INFINITY, i32::MAX, bag.clone());
+    // Highest log likelihood of every count of every colour
+    let mut profiles = vec![vec![f64::NEG_INFINITY; extra as usize + 1]; colours.len()];
+
+    loop {
+        let total: i32 = bag.iter().sum();
+        let log_likelihood: f64 = sets
+            .iter()
+            .map(|(counts, drawn)| {
+                let ways: f64 = bag.iter().zip(counts).map(|(&n, &k)| ln_choose(n, k)).sum();
+                ways - ln_choose(total, *drawn)
+            })
+            .sum();
+
+        let tie = (log_likelihood - best.0).abs() <= 1e-9;
+        if log_likelihood > best.0 + 1e-9 || (tie && total < best.1) {
+            best = (log_likelihood, total, bag.clone());
+        }
+        for (profile, (&n, &(_, minimum))) in profiles.iter_mut().zip(bag.iter().zip(&colours)) {
+            let entry = &mut profile[(n - minimum) as usize];
+            *entry = entry.max(log_likelihood);
+        }
+
+        // Next bag, counting up like an odometer
+        let Some(idx) = (0..bag.len()).find(|&idx| bag[idx] < colours[idx].1 + extra) else {
+            break;
+        };
+        bag[idx] += 1;
+        for (count, &(_, minimum)) in bag[..idx].iter_mut().zip(&colours) {
+            *count = minimum;
+        }
+    }
+
+    let threshold = best.0 - chi_squared_quantile(options.confidence) / 2.0;
+    let estimates = colours
+        .iter()
+        .zip(&profiles)
+        .zip(&best.2)
+        .map(|((&(colour, minimum), profile), &likely)| {
+            let within: Vec<i32> = (0..)
+                .zip(profile)
+                .filter(|&(_, &l)| l >= threshold - 1e-9)
+                .map(|(offset, _)| minimum + offset)
+                .collect();
+            let high = *within.last().unwrap_or(&likely);
+
+            Estimate {
+                colour: colour.clone(),
+                minimum,
+                likely,
+                low: *within.first().unwrap_or(&likely),
+                high,
+                open: high == minimum + extra,
+            }
+        })
+        .collect();
+
+    Ok(Inference {
+        game: game.id,
+        estimates,
+        confidence: options.confidence,
+    })
+}
+
+fn candidates(colours: usize, extra: i32) -> usize {
+    (extra as usize + 1).saturating_pow(colours as u32)
+}
+
+fn ln_factorials(max: usize) -> Vec<f64> {
+    let mut table = vec![0.0; max + 1];
+    for n in 1..=max {
+        table[n] = table[n - 1] + (n as f64).ln();
+    }
+
+    table
+}
+
+/// Quantile of the chi-squared distribution with one degree of freedom.
+fn chi_squared_quantile(confidence: f64) -> f64 {
+    let confidence = confidence.clamp(0.0, 0.999_999);
+
+    // Solve erf(z / sqrt(2)) = confidence for z by bisection
+    let (mut low, mut high) = (0.0, 10.0);
+    for _ in 0..100 {
+        let z = (low + high) / 2.0;
+        if erf(z / std::f64::consts::SQRT_2) < confidence {
+            low = z;
+        } else {
+            high = z;
+        }
+    }
+
+    low * low
+}
+
+/// Abramowitz and Stegun 7.1.26, accurate to 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+
+    y.copysign(x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn game(input: &str) -> Game {
+        input.parse().unwrap()
+    }
+
+    fn likely_bag(inference: &Inference) -> Vec<(&str, i32)> {
+        inference
+            .estimates
+            .iter()
+            .map(|e| (e.colour.as_str(), e.likely))
+            .collect()
+    }
+
+    #[test]
+    fn test_chi_squared_quantile() {
+        assert!((chi_squared_quantile(0.95) - 3.841).abs() < 1e-3);
+        assert!((chi_squared_quantile(0.99) - 6.635).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_infer_minimum() {
+        // The minimum bag draws the only possible set with certainty
+        let inference = infer(&game("Game 1: 1 red, 1 blue"), &Options::default()).unwrap();
+        assert_eq!(likely_bag(&inference), [("blue", 1), ("red", 1)]);
+
+        let red = &inference.estimates[1];
+        assert_eq!((red.minimum, red.low), (1, 1));
+        assert!(red.high > 1);
+    }
+
+    #[test]
+    fn test_infer() {
+        let options = Options {
+            extra: 10,
+            confidence: 0.95,
+        };
+        let inference = infer(&game("Game 2: 2 red; 1 blue; 2 red"), &options).unwrap();
+        let [blue, red] = &inference.estimates[..] else {
+            panic!("expected two estimates");
+        };
+
+        // Red is drawn more often, so it is likely more common than blue
+        assert!(red.likely > blue.likely);
+        for estimate in [blue, red] {
+            assert!(estimate.minimum <= estimate.low);
+            assert!(estimate.low <= estimate.likely && estimate.likely <= estimate.high);
+        }
+        assert!(red.open);
+        assert_eq!(red.high, red.minimum + 10);
+
+        assert!(inference.to_string().starts_with("game 2: blue 1 (likely "));
+    }
+
+    #[test]
+    fn test_infer_limits_search() {
+        let colours = game("Game 3: 1 a, 1 b, 1 c, 1 d, 1 e, 1 f, 1 g, 1 h");
+        let inference = infer(&colours, &Options::default()).unwrap();
+
+        // 20 extra cubes of eight colours are too many bags to search
+        assert!(inference.estimates.iter().all(|e| e.high < e.minimum + 20));
+
+        let options = Options {
+            extra: i32::MAX,
+            ..Options::default()
+        };
+        let inference = infer(&game("Game 4: 999990 red"), &options).unwrap();
+        // The extra cubes stop at the most cubes the search can hold
+        assert_eq!(inference.estimates[0].high, MAX_CUBES as i32);
+    }
+
+    #[test]
+    fn test_infer_too_many_cubes() {
+        let error =
+            infer(&game("Game 1: 1000000000 red, 5 blue"), &Options::default()).unwrap_err();
+
+        assert_eq!(
+            error,
+            TooManyCubes {
+                game: 1,
+                cubes: 1_000_000_005
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "game 1 needs at least 1000000005 cubes, at most 1000000 can be searched"
+        );
+    }
+}
@@ -0,0 +1,641 @@
+//! Filter and aggregate expressions over day 2 games, e.g.
+//! `max(blue) > 10 and count(sets) >= 3`.
+//!
+//! Values of a game are `id`, `power`, `count(sets)`, `count(colour)` (sets
+//! drawing the colour) and `max`, `min`, `sum` or `avg` of a colour over the
+//! sets. Numbers combine with `+ - * /`, comparisons with `and`, `or`, `not`
+//! and parentheses.
+
+use std::{fmt, str::FromStr};
+
+use crate::two::Game;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Max,
+    Min,
+    Sum,
+    Avg,
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arithmetic {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Id,
+    Power,
+    Sets,
+    Call(Function, String),
+    Negate(Box<Expr>),
+    Arithmetic(Arithmetic, Box<Expr>, Box<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    fn is_condition(&self) -> bool {
+        matches!(
+            self,
+            Expr::Compare(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(_)
+        )
+    }
+
+    fn number(&self, game: &Game) -> f64 {
+        fn counts<'a>(game: &'a Game, colour: &'a str) -> impl Iterator<Item = f64> + 'a {
+            game.sets.iter().map(|s| f64::from(s.count(colour)))
+        }
+
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Id => f64::from(game.id),
+            Expr::Power => f64::from(game.power()),
+            Expr::Sets => game.sets.len() as f64,
+            Expr::Call(function, colour) => match function {
+                Function::Max => counts(game, colour).fold(0.0, f64::max),
+                Function::Min => counts(game, colour).reduce(f64::min).unwrap_or(0.0),
+                Function::Sum => counts(game, colour).sum(),
+                Function::Avg => counts(game, colour).sum::<f64>() / game.sets.len().max(1) as f64,
+                Function::Count => counts(game, colour).filter(|&c| c > 0.0).count() as f64,
+            },
+            Expr::Negate(expr) => -expr.number(game),
+            Expr::Arithmetic(op, left, right) => {
+                let (left, right) = (left.number(game), right.number(game));
+                match op {
+                    Arithmetic::Add => left + right,
+                    Arithmetic::Subtract => left - right,
+                    Arithmetic::Multiply => left * right,
+                    Arithmetic::Divide => left / right,
+                }
+            }
+            _ => unreachable!("conditions are rejected when parsing"),
+        }
+    }
+
+    fn condition(&self, game: &Game) -> bool {
+        match self {
+            Expr::Compare(op, left, right) => {
+                let (left, right) = (left.number(game), right.number(game));
+                match op {
+                    Comparison::Less => left < right,
+                    Comparison::LessEqual => left <= right,
+                    Comparison::Greater => left > right,
+                    Comparison::GreaterEqual => left >= right,
+                    Comparison::Equal => left == right,
+                    Comparison::NotEqual => left != right,
+                }
+            }
+            Expr::And(left, right) => left.condition(game) && right.condition(game),
+            Expr::Or(left, right) => left.condition(game) || right.condition(game),
+            Expr::Not(expr) => !expr.condition(game),
+            _ => unreachable!("numbers are rejected when parsing"),
+        }
+    }
+}
+
+/// Condition selecting games, e.g. `max(blue) > 10 and count(sets) >= 3`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(Expr);
+
+impl Filter {
+    pub fn matches(&self, game: &Game) -> bool {
+        self.0.condition(game)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = Parser::new(s)?.parse()?;
+        if !expr.is_condition() {
+            return Err(QueryError::new(1, "a condition, e.g. `max(red) > 12`"));
+        }
+
+        Ok(Self(expr))
+    }
+}
+
+/// Number computed for every game, e.g. `avg(red)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+    source: String,
+    expr: Expr,
+}
+
+impl Value {
+    pub fn evaluate(&self, game: &Game) -> f64 {
+        self.expr.number(game)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for Value {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = Parser::new(s)?.parse()?;
+        if expr.is_condition() {
+            return Err(QueryError::new(1, "a number, e.g. `avg(red)`"));
+        }
+
+        Ok(Self {
+            source: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+/// Summary of a value over the matching games.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aggregate {
+    pub sum: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Matching game ids and aggregates of the values over them, `None` when no
+/// game matches.
+pub fn query(
+    games: &[Game],
+    filter: &Filter,
+    values: &[Value],
+) -> (Vec<i32>, Vec<Option<Aggregate>>) {
+    let matching: Vec<&Game> = games.iter().filter(|g| filter.matches(g)).collect();
+
+    let aggregates = values
+        .iter()
+        .map(|value| {
+            let results: Vec<f64> = matching.iter().map(|g| value.evaluate(g)).collect();
+            let sum: f64 = results.iter().sum();
+
+            (!results.is_empty()).then(|| Aggregate {
+                sum,
+                mean: sum / results.len() as f64,
+                min: results.iter().copied().fold(f64::INFINITY, f64::min),
+                max: results.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            })
+        })
+        .collect();
+
+    (matching.iter().map(|g| g.id).collect(), aggregates)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Symbol(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "`{value}`"),
+            Token::Word(word) => write!(f, "`{word}`"),
+            Token::Symbol(symbol) => write!(f, "`{symbol}`"),
+            Token::End => write!(f, "the end"),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 12] = [
+    ">=", "<=", "==", "!=", ">", "<", "(", ")", "+", "-", "*", "/",
+];
+
+struct Parser {
+    /// Tokens with their 1-based column.
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, QueryError> {
+        let mut tokens = vec![];
+        let chars: Vec<char> = source.chars().collect();
+        let mut idx = 0;
+
+        while idx < chars.len() {
+            let column = idx + 1;
+            let c = chars[idx];
+
+            if c.is_whitespace() {
+                idx += 1;
+            } else if c.is_ascii_digit() || c == '.' {
+                let end = (idx..chars.len())
+                    .find(|&i| !(chars[i].is_ascii_digit() || chars[i] == '.'))
+                    .unwrap_or(chars.len());
+                let number: String = chars[idx..end].iter().collect();
+                let number = number
+                    .parse()
+                    .map_err(|_| QueryError::new(column, "a number"))?;
+                tokens.push((Token::Number(number), column));
+                idx = end;
+            } else if c.is_alphabetic() || c == '_' {
+                let end = (idx..chars.len())
+                    .find(|&i| !(chars[i].is_alphanumeric() || chars[i] == '_'))
+                    .unwrap_or(chars.len());
+                tokens.push((Token::Word(chars[idx..end].iter().collect()), column));
+                idx = end;
+            } else {
+                let rest: String = chars[idx..(idx + 2).min(chars.len())].iter().collect();
+                let symbol = SYMBOLS
+                    .into_iter()
+                    .find(|s| rest.starts_with(s))
+                    .ok_or_else(|| QueryError::new(column, "an operator, number or name"))?;
+                tokens.push((Token::Symbol(symbol), column));
+                idx += symbol.len();
+            }
+        }
+        tokens.push((Token::End, chars.len() + 1));
+
+        Ok(Self {
+            tokens,
+            position: 0,
+        })
+    }
+
+    fn parse(mut self) -> Result<Expr, QueryError> {
+        let expr = self.or()?;
+        match self.peek() {
+            Token::End => Ok(expr),
+            _ => Err(self.error("an operator or the end")),
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].0.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn error(&self, expected: &str) -> QueryError {
+        let (token, column) = &self.tokens[self.position];
+        QueryError::new(*column, &format!("{expected}, found {token}"))
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Token::Word(w) if w == word);
+        if found {
+            self.position += 1;
+        }
+
+        found
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<(), QueryError> {
+        if self.peek() != &Token::Symbol(symbol) {
+            return Err(self.error(&format!("`{symbol}`")));
+        }
+        self.position += 1;
+
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position;
+        let mut expr = self.and()?;
+
+        while matches!(self.peek(), Token::Word(w) if w == "or") {
+            self.require_condition(&expr, start)?;
+            self.position += 1;
+
+            let start = self.position;
+            let right = self.and()?;
+            self.require_condition(&right, start)?;
+            expr = Expr::Or(Box::new(expr), Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position;
+        let mut expr = self.not()?;
+
+        while matches!(self.peek(), Token::Word(w) if w == "and") {
+            self.require_condition(&expr, start)?;
+            self.position += 1;
+
+            let start = self.position;
+            let right = self.not()?;
+            self.require_condition(&right, start)?;
+            expr = Expr::And(Box::new(expr), Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if !self.eat_word("not") {
+            return self.comparison();
+        }
+
+        let start = self.position;
+        let expr = self.not()?;
+        self.require_condition(&expr, start)?;
+
+        Ok(Expr::Not(Box::new(expr)))
+    }
+
+    /// Operands of `and`, `or` and `not` must be conditions, `start` is the
+    /// first token of the operand.
+    fn require_condition(&mut self, expr: &Expr, start: usize) -> Result<(), QueryError> {
+        if !expr.is_condition() {
+            self.position = start;
+            return Err(self.error("a condition"));
+        }
+
+        Ok(())
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let left = self.additive()?;
+
+        let op = match self.peek() {
+            Token::Symbol("<") => Comparison::Less,
+            Token::Symbol("<=") => Comparison::LessEqual,
+            Token::Symbol(">") => Comparison::Greater,
+            Token::Symbol(">=") => Comparison::GreaterEqual,
+            Token::Symbol("==") => Comparison::Equal,
+            Token::Symbol("!=") => Comparison::NotEqual,
+            _ => return Ok(left),
+        };
+        if left.is_condition() {
+            return Err(self.error("`and`, `or` or `)`"));
+        }
+        self.next();
+
+        let right = self.additive()?;
+        if right.is_condition() {
+            return Err(QueryError::new(
+                self.tokens[self.position - 1].1,
+                "a number to compare with",
+            ));
+        }
+
+        Ok(Expr::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position;
+        let mut expr = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol("+") => Arithmetic::Add,
+                Token::Symbol("-") => Arithmetic::Subtract,
+                _ => return Ok(expr),
+            };
+            self.require_number(&expr, start)?;
+            self.next();
+
+            let start = self.position;
+            let right = self.multiplicative()?;
+            self.require_number(&right, start)?;
+            expr = Expr::Arithmetic(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position;
+        let mut expr = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Symbol("*") => Arithmetic::Multiply,
+                Token::Symbol("/") => Arithmetic::Divide,
+                _ => return Ok(expr),
+            };
+            self.require_number(&expr, start)?;
+            self.next();
+
+            let start = self.position;
+            let right = self.unary()?;
+            self.require_number(&right, start)?;
+            expr = Expr::Arithmetic(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    /// Operands of arithmetic and `-` must be numbers, `start` is the first
+    /// token of the operand.
+    fn require_number(&mut self, expr: &Expr, start: usize) -> Result<(), QueryError> {
+        if expr.is_condition() {
+            self.position = start;
+            return Err(self.error("a number"));
+        }
+
+        Ok(())
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.peek() == &Token::Symbol("-") {
+            self.next();
+
+            let start = self.position;
+            let expr = self.unary()?;
+            self.require_number(&expr, start)?;
+            return Ok(Expr::Negate(Box::new(expr)));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position;
+
+        match self.next() {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Symbol("(") => {
+                let expr = self.or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Word(word) => {
+                let function = match word.as_str() {
+                    "id" => return Ok(Expr::Id),
+                    "power" => return Ok(Expr::Power),
+                    "max" => Function::Max,
+                    "min" => Function::Min,
+                    "sum" => Function::Sum,
+                    "avg" => Function::Avg,
+                    "count" => Function::Count,
+                    _ => {
+                        self.position = start;
+                        return Err(self.error("`id`, `power` or a function"));
+                    }
+                };
+
+                self.expect("(")?;
+                let Token::Word(colour) = self.next() else {
+                    self.position -= 1;
+                    return Err(self.error("a colour"));
+                };
+                self.expect(")")?;
+
+                Ok(match (function, colour.as_str()) {
+                    (Function::Count, "sets") => Expr::Sets,
+                    _ => Expr::Call(function, colour),
+                })
+            }
+            _ => {
+                self.position = start;
+                Err(self.error("a number, name or `(`"))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    /// 1-based column of the error.
+    pub column: usize,
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(column: usize, expected: &str) -> Self {
+        Self {
+            column,
+            message: format!("expected {expected}"),
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "query, column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::two::parse_input;
+
+    const SAMPLE_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn matching(filter: &str) -> Vec<i32> {
+        let games = parse_input(SAMPLE_INPUT).unwrap();
+        query(&games, &filter.parse().unwrap(), &[]).0
+    }
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(matching("max(blue) > 10"), [4]);
+        assert_eq!(matching("max(blue) > 5 and count(sets) >= 3"), [1, 3, 4]);
+        assert_eq!(
+            matching("not (max(red) > 12 or max(green) > 13)"),
+            [1, 2, 5]
+        );
+        assert_eq!(matching("count(red) == 2 or id == 1"), [1, 5]);
+        assert_eq!(matching("power / 2 >= 600 - 2 * 10"), [3]);
+        assert_eq!(matching("min(green) != 0"), [2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let games = parse_input(SAMPLE_INPUT).unwrap();
+        let filter: Filter = "id <= 2".parse().unwrap();
+        let values: Vec<Value> = ["avg(red)", "sum(green) + -1"]
+            .iter()
+            .map(|v| v.parse().unwrap())
+            .collect();
+
+        let (ids, aggregates) = query(&games, &filter, &values);
+        assert_eq!(ids, [1, 2]);
+
+        // Game 1 averages 5 / 3 red per set, game 2 1 / 3
+        let red = aggregates[0].unwrap();
+        assert_eq!((red.sum, red.mean), (2.0, 1.0));
+        let green = aggregates[1].unwrap();
+        assert_eq!((green.min, green.max), (3.0, 5.0));
+
+        let none: Filter = "id > 5".parse().unwrap();
+        assert_eq!(query(&games, &none, &values).1, [None, None]);
+    }
+
+    #[test]
+    fn test_invalid_query() {
+        let error = |query: &str| query.parse::<Filter>().unwrap_err().to_string();
+
+        assert_eq!(
+            error("max(blue) > > 3"),
+            "query, column 13: expected a number, name or `(`, found `>`"
+        );
+        assert_eq!(
+            error("blue > 3"),
+            "query, column 1: expected `id`, `power` or a function, found `blue`"
+        );
+        assert_eq!(
+            error("max(blue"),
+            "query, column 9: expected `)`, found the end"
+        );
+        assert_eq!(
+            error("max(blue) and id > 1"),
+            "query, column 1: expected a condition, found `max`"
+        );
+        assert_eq!(
+            error("id + 1"),
+            "query, column 1: expected a condition, e.g. `max(red) > 12`"
+        );
+        assert_eq!(
+            error("id > 1 $"),
+            "query, column 8: expected an operator, number or name"
+        );
+        assert_eq!(
+            error("id + (id > 1) > 0"),
+            "query, column 6: expected a number, found `(`"
+        );
+        assert_eq!(
+            error("-(id > 1) > 0"),
+            "query, column 2: expected a number, found `(`"
+        );
+        assert_eq!(
+            error("(id > 1) * 2 > 0"),
+            "query, column 1: expected a number, found `(`"
+        );
+        assert!("max(red) > 1".parse::<Value>().is_err());
+        assert_eq!(
+            "id * (id > 1)".parse::<Value>().unwrap_err().to_string(),
+            "query, column 6: expected a number, found `(`"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ParseError,
+    registry::DAYS,
+    solver::{Answer, Day, Part},
+};
+
+/// Default location of the expected answers, relative to the repository root.
+pub const MANIFEST_PATH: &str = "answers.toml";
+
+const MANIFEST_HEADER: &str = "\
+# Expected answers of the cached puzzle inputs, keyed by day name.
+# Checked by `advent_of_code_2023 verify` and `cargo test verify`.
+";
+
+/// Expected answers keyed by the day name used in [`crate::registry::DAYS`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest(BTreeMap<String, Expected>);
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expected {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_one: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_two: Option<String>,
+}
+
+impl Manifest {
+    pub fn expected(&self, day: &str, part: Part) -> Option<&str> {
+        let expected = self.0.get(day)?;
+
+        match part {
+            Part::One => expected.part_one.as_deref(),
+            Part::Two => expected.part_two.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, day: &str, part: Part, answer: &Answer) {
+        let expected = self.0.entry(day.to_string()).or_default();
+        let answer = Some(answer.to_string());
+
+        match part {
+            Part::One => expected.part_one = answer,
+            Part::Two => expected.part_two = answer,
+        }
+    }
+
+    /// Stores the answers of all outcomes that have no expected answer yet.
+    pub fn record(&mut self, report: &Report) {
+        for outcome in &report.0 {
+            if let Status::Unverified(answer) = &outcome.status {
+                self.set(outcome.name, outcome.part, answer);
+            }
+        }
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let manifest = toml::to_string(self).map_err(|_| fmt::Error)?;
+
+        write!(f, "{MANIFEST_HEADER}\n{manifest}")
+    }
+}
+
+/// Result of checking a single part of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Passed(Answer),
+    Failed {
+        expected: String,
+        actual: Answer,
+    },
+    /// The manifest has no answer for this part yet.
+    Unverified(Answer),
+    MissingInput,
+    InvalidInput(ParseError),
+    NotImplemented,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub day: u8,
+    pub name: &'static str,
+    pub part: Part,
+    pub status: Status,
+}
+
+/// Outcomes of every registered day and part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report(pub Vec<Outcome>);
+
+impl Report {
+    /// Failed parts, the report is successful when this is empty.
+    pub fn failures(&self) -> impl Iterator<Item = &Outcome> {
+        self.0
+            .iter()
+            .filter(|o| matches!(o.status, Status::Failed { .. } | Status::InvalidInput(_)))
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Solved parts without an expected answer in the manifest.
+    pub fn unverified(&self) -> impl Iterator<Item = &Outcome> {
+        self.0
+            .iter()
+            .filter(|o| matches!(o.status, Status::Unverified(_)))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut skipped = 0;
+
+        for outcome in &self.0 {
+            let status = match &outcome.status {
+                Status::Passed(answer) => {
+                    passed += 1;
+                    format!("ok ({answer})")
+                }
+                Status::Failed { expected, actual } => {
+                    failed += 1;
+                    format!("MISMATCH expected {expected}, got {actual}")
+                }
+                Status::InvalidInput(err) => {
+                    failed += 1;
+                    format!("INVALID INPUT {err}")
+                }
+                Status::Unverified(answer) => {
+                    skipped += 1;
+                    format!("unverified ({answer})")
+                }
+                Status::MissingInput => {
+                    skipped += 1;
+                    "skipped, missing input".to_string()
+                }
+                Status::NotImplemented => continue,
+            };
+
+            writeln!(f, "day {} part {}: {status}", outcome.day, outcome.part)?;
+        }
+
+        write!(f, "{passed} passed, {failed} failed, {skipped} skipped")
+    }
+}
+
+/// Solves every registered day with the input returned by `load_input` and
+/// compares the answers with the manifest.
+pub fn verify<F>(manifest: &Manifest, mut load_input: F) -> Report
+where
+    F: FnMut(&Day) -> Option<String>,
+{
+    let mut outcomes = vec![];
+
+    for day in DAYS {
+        let parsed = load_input(day).map(|input| day.parse(&input));
+
+        for part in Part::ALL {
+            let status = match &parsed {
+                None => Status::MissingInput,
+                Some(Err(err)) => Status::InvalidInput(err.clone()),
+                Some(Ok(parsed)) => match day.solve(parsed, part) {
+                    None => Status::NotImplemented,
+                    Some(Err(err)) => Status::InvalidInput(err),
+                    Some(Ok(actual)) => match manifest.expected(day.name, part) {
+                        None => Status::Unverified(actual),
+                        Some(expected) if expected == actual.as_str() => Status::Passed(actual),
+                        Some(expected) => Status::Failed {
+                            expected: expected.to_string(),
+                            actual,
+                        },
+                    },
+                },
+            };
+
+            outcomes.push(Outcome {
+                day: day.number,
+                name: day.name,
+                part,
+                status,
+            });
+        }
+    }
+
+    Report(outcomes)
+}
+
+/// Expected answer of a day for the `solution_*` tests, read from
+/// [`MANIFEST_PATH`].
+#[cfg(test)]
+pub(crate) fn expected_answer(name: &str, part: Part) -> String {
+    let manifest = std::fs::read_to_string(MANIFEST_PATH).unwrap();
+    let manifest: Manifest = manifest.parse().unwrap();
+
+    manifest
+        .expected(name, part)
+        .unwrap_or_else(|| panic!("no answer for {name} part {part} in {MANIFEST_PATH}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+    use crate::input::Config;
+
+    const SAMPLE_MANIFEST: &str = "
+[two]
+part_one = \"8\"
+part_two = \"2285\"
+";
+
+    const SAMPLE_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn sample_report(manifest: &Manifest) -> Report {
+        verify(manifest, |day| {
+            (day.number == 2).then(|| SAMPLE_INPUT.to_string())
+        })
+    }
+
+    #[test]
+    fn test_verify() {
+        let report = sample_report(&SAMPLE_MANIFEST.parse().unwrap());
+
+        let statuses: Vec<&Status> = report
+            .0
+            .iter()
+            .filter(|o| o.day == 2)
+            .map(|o| &o.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                &Status::Passed(8.into()),
+                &Status::Failed {
+                    expected: "2285".to_string(),
+                    actual: 2286.into()
+                }
+            ]
+        );
+
+        assert!(!report.is_success());
+        assert!(report
+            .to_string()
+            .contains("day 2 part 2: MISMATCH expected 2285, got 2286"));
+    }
+
+    #[test]
+    fn test_record() {
+        let mut manifest = Manifest::default();
+        let report = sample_report(&manifest);
+        assert_eq!(report.unverified().count(), 2);
+        manifest.record(&report);
+        assert_eq!(sample_report(&manifest).unverified().count(), 0);
+
+        let manifest: Manifest = manifest.to_string().parse().unwrap();
+        assert_eq!(manifest.expected("two", Part::One), Some("8"));
+        assert_eq!(manifest.expected("two", Part::Two), Some("2286"));
+        assert_eq!(manifest.expected("one", Part::One), None);
+    }
+
+    /// Checks the answers of the cached inputs against `answers.toml`.
+    #[test]
+    fn verify_answers() {
+        let manifest = fs::read_to_string(MANIFEST_PATH).unwrap();
+        let manifest: Manifest = manifest.parse().unwrap();
+
+        let config = Config::load().unwrap();
+        let report = verify(&manifest, |day| config.read(day).ok());
+
+        assert!(report.is_success(), "{report}");
+        assert!(
+            report.unverified().next().is_none(),
+            "answers missing from {MANIFEST_PATH}, run `verify --record`\n{report}"
+        );
+    }
+}